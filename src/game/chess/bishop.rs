@@ -1,68 +1,36 @@
-use std::{collections::{HashMap, HashSet}, f32::consts::PI};
+use std::f32::consts::PI;
 
-use bevy::{prelude::*, input::mouse::MouseButtonInput};
+use bevy::{prelude::*, ecs::system::EntityCommands};
 use bevy_mod_picking::prelude::{On, Click, Pointer, Listener, PointerButton};
 use hexx::Hex;
 
-use crate::game::graphics_3d::honeycomb::{HEX_SIZE, MAP_RADIUS, Map, Honeycomb};
+use crate::game::graphics_3d::honeycomb::{Map, Honeycomb, AimTarget};
 
-use super::pawn::{SpawnAnimToggle, OtherSpawn, SpawnCategory};
-
-#[derive(Resource)]
-pub struct BishopRes{
-    //scene: Handle<Scene>,
-    mesh: Handle<Mesh>,
-    bishop_list: HashMap<Hex, Entity>,
-    blue_bishop_list: HashSet<Hex>,
-    red_bishop_list: HashSet<Hex>,
-    spawn_animation: (Handle<AnimationClip>, Name),
-    idle_animation: (Handle<AnimationClip>, Name),
-    control_target: Option<Hex>
-}
+use super::{piece_visual::{PieceVisual, PieceRes}, rules::RulesRes, interaction::{InteractionMode, AimEvent, begin_aim}, game_sim::{GameSim, SimEvent, PlayerControl, ActionEvent}, simulation::{PieceId, PieceKind, GameEvent, GameAction, HexBoard, RayStep}};
 
 #[derive(Component)]
 pub struct Bishop{
-    blue_team: bool,
-    pos: Hex,
-    is_atack: bool
+    id: PieceId
 }
 
+//날아가는 로켓, 타일에서 떨어진 최상위 엔티티로 시뮬레이션의 진행도를 따라감
 #[derive(Component)]
 pub struct BishopAttack{
-    attack_target: Hex,
-    speed: f32,
-    move_time: f32
+    id: PieceId,
+    attack_target: Hex
 }
 
-#[derive(Component)]
-pub struct Attacking;
+impl PieceVisual for Bishop{
+    const KIND: PieceKind = PieceKind::Bishop;
+    const NAME: &'static str = "bishop";
 
-pub fn setup_asset_bishop(
-    mut commands: Commands,
-    assets_server: Res<AssetServer>,
-    mut animations: ResMut<Assets<AnimationClip>>,
-){
-    let spawn_anim = Name::new("bishop_spawn");
-    let mut spawn_animation = AnimationClip::default();
-    spawn_animation.add_curve_to_path(
-        EntityPath{parts: vec![spawn_anim.clone()]}, 
-        VariableCurve { 
-            keyframe_timestamps: vec![0.,0.6,1.2], 
-            keyframes: Keyframes::Translation(
-                vec![
-                    Vec3::new(0., HEX_SIZE * -2., 0.),
-                    Vec3::new(0., HEX_SIZE * 1.2, 0.),
-                    Vec3::new(0., HEX_SIZE / 3., 0.)
-                ]
-            ) 
-        }
-    );
-    let idle_anim = Name::new("bishop_idle");
-    let mut idle_animation = AnimationClip::default();
-    idle_animation.add_curve_to_path(
-        EntityPath { parts: vec![idle_anim.clone()] }, 
-        VariableCurve { 
-            keyframe_timestamps: vec![0.,1.5,2.5,3.5,5.,6.5,7.,8.5,9.], 
+    fn new(id: PieceId) -> Self{
+        Self { id }
+    }
+
+    fn idle_curve() -> VariableCurve{
+        VariableCurve {
+            keyframe_timestamps: vec![0.,1.5,2.5,3.5,5.,6.5,7.,8.5,9.],
             keyframes: Keyframes::Rotation(
                 vec![
                     Quat::from_euler(EulerRot::XYZ, 0., 0., 0.),
@@ -77,386 +45,105 @@ pub fn setup_asset_bishop(
                 ]
             )
         }
-    );
-
-    let spawn_animation_handle = animations.add(spawn_animation);
-    let idle_animation_handle = animations.add(idle_animation);
-    let map_size = (MAP_RADIUS * MAP_RADIUS) as usize;
-    commands.insert_resource(
-        BishopRes{
-            //scene: assets_server.load("pawn.glb#Scene0"),
-            mesh: assets_server.load("bishop.glb#Mesh0/Primitive0"),
-            blue_bishop_list: HashSet::with_capacity(map_size),
-            red_bishop_list:HashSet::with_capacity(map_size),
-            bishop_list: HashMap::with_capacity(map_size),
-            spawn_animation: (spawn_animation_handle, spawn_anim),
-            idle_animation:(idle_animation_handle, idle_anim),
-            control_target: None
-        }
-    );
-}
-
-pub fn bishop_spawn_event(
-    mut commands: Commands,
-    mut res_map: ResMut<Map>,
-    mut res_bishop: ResMut<BishopRes>,
-    mut events_bishop: EventReader<OtherSpawn>
-){ 
-    for ev in events_bishop.iter(){
-        match ev.category {
-            SpawnCategory::Bishop => {
-                let mesh = res_bishop.mesh.clone();
-                let mat = 
-                    if ev.blue_team {res_map.blue_mat.clone()} else {res_map.red_mat.clone()};
-                if ev.blue_team{
-                    res_bishop.blue_bishop_list.insert(ev.base_pos);
-                }else {
-                    res_bishop.red_bishop_list.insert(ev.base_pos);
-                }
-                let mut player = AnimationPlayer::default();
-                player.play(res_bishop.spawn_animation.0.clone());
-                let mut trans = Transform::from_xyz(0., HEX_SIZE/3. , 0.);
-                trans.scale = Vec3{x:0.4, y:0.4, z:0.4};
-
-                let tile = res_map.entities[&ev.base_pos];
-                res_map.blue_entities.insert(tile);
-                let mut entity = Entity::from_bits(0);
-                commands.entity(tile).with_children(|p|{
-                    entity = p.spawn(
-                        (
-                            PbrBundle{
-                                mesh,
-                                material: mat,
-                                transform: trans,
-                                ..Default::default()
-                            },
-                            Bishop{
-                                blue_team: ev.blue_team,
-                                pos: ev.base_pos,
-                                is_atack: false
-                            },
-                            res_bishop.spawn_animation.1.clone(),
-                            player,
-                            SpawnAnimToggle
-                        )
-                    ).id();
-                    res_bishop.bishop_list.insert(ev.base_pos, entity);
-                });
-                commands.entity(tile).insert(
-                  On::<Pointer<Click>>::run(on_bishop_click)
-                );
-                
-            },
-            _=>{}
-        }
     }
 
+    fn on_spawn(tile: &mut EntityCommands){
+        tile.insert(On::<Pointer<Click>>::run(on_bishop_click));
+    }
 }
 
 fn on_bishop_click(
     mut commands: Commands,
     event: Listener<Pointer<Click>>,
     mut res_map: ResMut<Map>,
-    mut res_bishop: ResMut<BishopRes>
+    mut res_mode: ResMut<InteractionMode>,
+    res_control: Res<PlayerControl>
 ){
     if event.button == PointerButton::Primary{
-        let target = event.target;
-        let base_tile = res_map.entities_forentity[&target];
-        res_bishop.control_target = Some(base_tile);
-        let mut count = Hex{x: 1, y: 0};
-        loop {
-            let paint_tile = base_tile + count;
-            let Some(block) = res_map.entities.get(&paint_tile) else {break};
-            if res_map.red_entities.contains(block){
-                break;
-            }
-            commands.entity(block.clone()).insert(
-                On::<Pointer<Click>>::run(on_bishop_path_set_10)  
-            );
-            res_map.path_list.insert(paint_tile);
-            count += Hex{x:1, y:0};
-        }
-        let mut count = Hex{x: -1, y: 0};
-        loop {
-            let paint_tile = base_tile + count;
-            let Some(block) = res_map.entities.get(&paint_tile) else {break};
-            if res_map.red_entities.contains(block){
-                break;
-            }
-            commands.entity(block.clone()).insert(
-                On::<Pointer<Click>>::run(on_bishop_path_set_m10)  
-            );
-            res_map.path_list.insert(paint_tile);
-            count += Hex{x:-1, y:0};
-        }
-        let mut count = Hex{x: 0, y: 1};
-        loop {
-            let paint_tile = base_tile + count;
-            let Some(block) = res_map.entities.get(&paint_tile) else {break};
-            if res_map.red_entities.contains(block){
-                break;
-            }
-            commands.entity(block.clone()).insert(
-                On::<Pointer<Click>>::run(on_bishop_path_set_01)  
-            );
-            res_map.path_list.insert(paint_tile);
-            count += Hex{x:0, y:1};
-        }
-        let mut count = Hex{x: 0, y: -1};
-        loop {
-            let paint_tile = base_tile + count;
-            let Some(block) = res_map.entities.get(&paint_tile) else {break};
-            if res_map.red_entities.contains(block){
-                break;
-            }
-            commands.entity(block.clone()).insert(
-                On::<Pointer<Click>>::run(on_bishop_path_set_0m1)  
-            );
-            res_map.path_list.insert(paint_tile);
-            count += Hex{x:0, y:-1};
-        }
-        let mut count = Hex{x: 1, y: -1};
-        loop {
-            let paint_tile = base_tile + count;
-            let Some(block) = res_map.entities.get(&paint_tile) else {break};
-            if res_map.red_entities.contains(block){
-                break;
-            }
-            commands.entity(block.clone()).insert(
-                On::<Pointer<Click>>::run(on_bishop_path_set_1m1)  
-            );
-            res_map.path_list.insert(paint_tile);
-            count += Hex{x:1, y:-1};
-        }
-        let mut count = Hex{x: -1, y: 1};
-        loop {
-            let paint_tile = base_tile + count;
-            let Some(block) = res_map.entities.get(&paint_tile) else {break};
-            if res_map.red_entities.contains(block){
-                break;
-            }
-            commands.entity(block.clone()).insert(
-                On::<Pointer<Click>>::run(on_bishop_path_set_m11)  
-            );
-            res_map.path_list.insert(paint_tile);
-            count += Hex{x:-1, y:1};
-        }
-        
-        for m in res_map.path_list.iter(){
-            let entity = res_map.entities[m];
-            commands.entity(entity).insert(
-                res_map.path_mat.clone(),
-            );
-        }
+        let base_tile = res_map.entities_forentity[&event.target];
+        let team = res_control.team;
+        //상대 팀의 비숍은 조종할 수 없음
+        if res_map.team_at(base_tile) != Some(team) {return}
+        if !begin_aim(&mut commands, &mut res_mode, &mut res_map, base_tile) {return}
+        //적 기물이나 바위가 있는 칸에서 경로가 끊김
+        res_map.offer_directions(&mut commands, base_tile, |map, hex|{
+            if map.is_enemy(hex, team) || map.is_blocked(hex) {RayStep::Stop} else {RayStep::Mark}
+        });
     }
 }
 
-pub fn cancel_path(
-    mut commands: Commands,
-    mut events_click: EventReader<MouseButtonInput>,
-    mut res_map: ResMut<Map>,
-    mut res_bishop: ResMut<BishopRes>
+pub fn bishop_aim_event(
+    mut events_aim: EventReader<AimEvent>,
+    mut events_action: EventWriter<ActionEvent>,
+    res_map: Res<Map>,
+    res_control: Res<PlayerControl>
 ){
-    for ev in events_click.iter(){
-        if ev.button == MouseButton::Left && !res_map.path_mod && res_bishop.control_target.is_none(){
-            for m in res_map.path_list.iter(){
-                let entity = res_map.entities[m];
-                commands.entity(entity).insert(
-                    if res_map.blue_entities.contains(&entity){
-                        res_map.blue_mat.clone()
-                    }else if res_map.red_entities.contains(&entity){
-                        res_map.red_mat.clone()
-                    }else{
-                        res_map.default_mat.clone()
-                    }
-                );
-                commands.entity(entity).remove::<On::<Pointer<Click>>>();
-            }
-            res_map.path_list.clear();
-        }
+    for ev in events_aim.iter(){
+        let AimTarget::Direction(direction) = ev.target else {continue};
+        if res_map.occupant_of(ev.piece, PieceKind::Bishop).is_none() {continue}
+        events_action.send(ActionEvent(GameAction::Launch { team: res_control.team, from: ev.piece, direction }));
     }
 }
 
-fn on_bishop_path_set_10(
-    mut commands: Commands,
-    event: Listener<Pointer<Click>>,
-    mut res_bishop: ResMut<BishopRes>,
-){
-    if let Some(control) = res_bishop.control_target{
-        let bishop_entity = res_bishop.bishop_list[&control];
-        if event.button == PointerButton::Primary{
-            commands.entity(bishop_entity).insert(
-                BishopAttack{
-                    attack_target: Hex::new(1,0),
-                    speed: 1.,
-                    move_time: 0.
-                }  
-            );
-        }
-        res_bishop.control_target = None;
-    };
-}
-fn on_bishop_path_set_01(
-    mut commands: Commands,
-    event: Listener<Pointer<Click>>,
-    mut res_bishop: ResMut<BishopRes>,
-){
-    if let Some(control) = res_bishop.control_target{
-        let bishop_entity = res_bishop.bishop_list[&control];
-        if event.button == PointerButton::Primary{
-            commands.entity(bishop_entity).insert(
-                BishopAttack{
-                    attack_target: Hex::new(0,1),
-                    speed: 1.,
-                    move_time: 0.
-                }  
-            );
-        }
-        res_bishop.control_target = None;
-    };
-}
-fn on_bishop_path_set_m10(
-    mut commands: Commands,
-    event: Listener<Pointer<Click>>,
-    mut res_bishop: ResMut<BishopRes>,
-){
-    if let Some(control) = res_bishop.control_target{
-        let bishop_entity = res_bishop.bishop_list[&control];
-        if event.button == PointerButton::Primary{
-            commands.entity(bishop_entity).insert(
-                BishopAttack{
-                    attack_target: Hex::new(-1,0),
-                    speed: 1.,
-                    move_time: 0.
-                }  
-            );
-        }
-        res_bishop.control_target = None;
-    };
-}
-fn on_bishop_path_set_0m1(
-    mut commands: Commands,
-    event: Listener<Pointer<Click>>,
-    mut res_bishop: ResMut<BishopRes>,
-){
-    if let Some(control) = res_bishop.control_target{
-        let bishop_entity = res_bishop.bishop_list[&control];
-        if event.button == PointerButton::Primary{
-            commands.entity(bishop_entity).insert(
-                BishopAttack{
-                    attack_target: Hex::new(0,-1),
-                    speed: 1.,
-                    move_time: 0.
-                }  
-            );
-        }
-        res_bishop.control_target = None;
-    };
-}
-fn on_bishop_path_set_m11(
-    mut commands: Commands,
-    event: Listener<Pointer<Click>>,
-    mut res_bishop: ResMut<BishopRes>,
-){
-    if let Some(control) = res_bishop.control_target{
-        let bishop_entity = res_bishop.bishop_list[&control];
-        if event.button == PointerButton::Primary{
-            commands.entity(bishop_entity).insert(
-                BishopAttack{
-                    attack_target: Hex::new(-1,1),
-                    speed: 1.,
-                    move_time: 0.
-                }  
-            );
-        }
-        res_bishop.control_target = None;
-    };
-}
-fn on_bishop_path_set_1m1(
-    mut commands: Commands,
-    event: Listener<Pointer<Click>>,
-    mut res_bishop: ResMut<BishopRes>,
-){
-    if let Some(control) = res_bishop.control_target{
-        let bishop_entity = res_bishop.bishop_list[&control];
-        if event.button == PointerButton::Primary{
-            commands.entity(bishop_entity).insert(
-                BishopAttack{
-                    attack_target: Hex::new(1,-1),
-                    speed: 1.,
-                    move_time: 0.
-                }  
-            );
-        }
-        res_bishop.control_target = None;
-    };
-}
-
-pub fn bishop_spawn_anim_is_end(
+pub fn bishop_launch_event(
     mut commands: Commands,
-    mut query_player: Query<(&mut AnimationPlayer, Entity), (With<SpawnAnimToggle>, With<Bishop>)>,
-    res_bishop: Res<BishopRes>,
+    mut events_sim: EventReader<SimEvent>,
+    mut res_map: ResMut<Map>,
+    res_piece: Res<PieceRes<Bishop>>,
+    res_rules: Res<RulesRes>,
+    query_bishop: Query<&Bishop>,
+    query_transform: Query<&GlobalTransform, With<Honeycomb>>
 ){
-    for (mut player, ent) in query_player.iter_mut(){
-        if player.elapsed() > 1.2{
-            let mut entity = commands.entity(ent);
-            entity.remove::<SpawnAnimToggle>();
-            player.play(res_bishop.idle_animation.0.clone()).repeat();
-            entity.insert(res_bishop.idle_animation.1.clone());
-        }
+    for SimEvent(ev) in events_sim.iter(){
+        let GameEvent::Launched { from, direction } = ev else {continue};
+        let Some(occupant) = res_map.vacate_kind(*from, PieceKind::Bishop) else {continue};
+        let Ok(bishop) = query_bishop.get(occupant.entity) else {continue};
+        let tile = res_map.entities[from];
+        commands.entity(tile).remove::<On::<Pointer<Click>>>();
+        commands.entity(tile).insert(res_map.tile_mat(*from));
+        commands.entity(occupant.entity).despawn_recursive();
+
+        //타일의 자식인 모델 대신 최상위 로켓을 띄움
+        let from_trans = query_transform.get(tile).unwrap().translation() + Vec3::Y * res_rules.hex_size/3.;
+        let mut trans = Transform::from_translation(from_trans);
+        trans.scale = Vec3::splat(Bishop::SCALE * res_rules.model_scale());
+        commands.spawn((
+            PbrBundle{
+                mesh: res_piece.mesh.clone(),
+                material: res_map.team_color(occupant.team),
+                transform: trans,
+                ..Default::default()
+            },
+            BishopAttack{
+                id: bishop.id,
+                attack_target: *direction
+            }
+        ));
     }
 }
 
 pub fn bishop_attacking(
     mut commands: Commands,
-    mut query_bishop: Query<(Entity,&mut Bishop, &mut BishopAttack, &GlobalTransform, &mut Transform)>,
+    mut query_rocket: Query<(Entity, &BishopAttack, &mut Transform)>,
     query_transform: Query<&GlobalTransform, With<Honeycomb>>,
-    mut res_bishop: ResMut<BishopRes>,
-    mut res_map: ResMut<Map>,
-    res_time: Res<Time>
+    res_sim: Res<GameSim>,
+    res_map: Res<Map>,
+    res_rules: Res<RulesRes>
 ){
-    for (entity,mut bishop, mut bishopat, globalt,mut trans)
-     in query_bishop.iter_mut(){
-        let tile = res_map.entities[&bishop.pos];
-        if !bishop.is_atack{
-            res_bishop.bishop_list.remove(&bishop.pos);
-            commands.entity(tile).remove::<On::<Pointer<Click>>>();
-            if bishop.blue_team{
-                res_map.blue_entities.remove(&tile);
-                res_bishop.blue_bishop_list.remove(&bishop.pos);
-            }else{
-                res_map.red_entities.remove(&tile);
-                res_bishop.red_bishop_list.remove(&bishop.pos);
-            }
-            bishop.is_atack = true;
-        }
-        if res_map.red_entities.contains(&tile){
+    for (entity, bishopat, mut trans) in query_rocket.iter_mut(){
+        //시뮬레이션에서 로켓이 사라졌다면 폭팔한것
+        let Some(rocket) = res_sim.state.rocket(bishopat.id) else {
             commands.entity(entity).despawn();
-            //원래는 여기에 록이 있는지 확인 후 폭팔해야함
-        }else{
-            let delta = res_time.delta_seconds();
-            bishopat.move_time += delta * bishopat.speed;
-            bishopat.speed += delta;
-
-            let target_pos = bishop.pos + bishopat.attack_target;
-            let target_entity = res_map.entities.get(&target_pos);
-            if target_entity.is_some(){
-                let target_entity = target_entity.unwrap();
-                let target_tarns = query_transform.get(target_entity.clone()).unwrap().translation();
-                let base_pos_trans = query_transform.get(res_map.entities[&bishop.pos]).unwrap().translation();
-
-                let move_trans = base_pos_trans * bishopat.move_time.max(1.) + target_tarns * (1. - bishopat.move_time.max(1.));
-                trans.translation = globalt.translation() - move_trans;
-                trans.rotation = Quat::from_euler(EulerRot::XYZ, PI / 4., 0., 0.);
-                trans.look_at(target_tarns, Vec3::Y);
-
-                if bishopat.move_time > 1. {
-                    bishop.pos = target_pos;
-                    bishopat.move_time = 0.;
-                }
-            }else{
-                commands.entity(entity).despawn();
-            }
-        }
+            continue;
+        };
+        let target_pos = rocket.pos + bishopat.attack_target;
+        let Some(target_entity) = res_map.entities.get(&target_pos) else {continue};
+        let height = Vec3::Y * res_rules.hex_size/3.;
+        let target_trans = query_transform.get(*target_entity).unwrap().translation() + height;
+        let base_trans = query_transform.get(res_map.entities[&rocket.pos]).unwrap().translation() + height;
+
+        let progress = rocket.progress.clamp(0., 1.);
+        trans.translation = base_trans.lerp(target_trans, progress);
+        trans.look_at(target_trans, Vec3::Y);
     }
 }
@@ -0,0 +1,146 @@
+use bevy::prelude::*;
+
+use crate::game::graphics_3d::honeycomb::Map;
+
+use super::{rules::RulesRes, picker::CombinationChoice, replay::MatchRecord, simulation::{GameState, GameEvent, GameAction, GameRules, ActionError, Team}};
+
+//시뮬레이션은 FixedUpdate에서 항상 같은 간격으로 진행됨
+pub const SIM_TIMESTEP: f32 = 1. / 60.;
+
+//bevy 쪽에서 시뮬레이션을 들고 있는 리소스
+#[derive(Resource)]
+pub struct GameSim{
+    pub state: GameState,
+    pub ticks: u64 //기물을 놓은 뒤 지난 스텝 수, 리플레이는 이 값으로 행동의 시간을 맞춤
+}
+
+impl GameSim{
+    //보드와 규칙으로 기물이 없는 새 판을 만듦
+    pub fn new(res_map: &Map, seed: Option<u64>, rules: &GameRules) -> Self{
+        let tiles = res_map.entities.keys().copied();
+        let mut state = match seed {
+            Some(seed) => GameState::with_seed(tiles, seed),
+            None => GameState::new(tiles)
+        };
+        state.set_rules(rules.clone());
+        state.set_blocked(res_map.blocked_coords.iter().copied());
+        Self { state, ticks: 0 }
+    }
+
+    //한 스텝 진행하고 나온 결과를 이벤트로 보냄
+    pub fn step(&mut self, delta: f32, events_sim: &mut EventWriter<SimEvent>){
+        self.state.tick(delta);
+        self.ticks += 1;
+        for ev in self.state.drain_events(){
+            events_sim.send(SimEvent(ev));
+        }
+    }
+}
+
+//다음 판에 쓸 시드, None이면 시작할 때 무작위로 정함
+#[derive(Resource, Default)]
+pub struct MatchSeed(pub Option<u64>);
+
+#[derive(Event)]
+pub struct SimEvent(pub GameEvent);
+
+//클릭, AI, 리플레이 모두 행동을 이 이벤트로 보내고 apply_actions 에서만 시뮬레이션에 적용함
+#[derive(Event, Debug, Clone)]
+pub struct ActionEvent(pub GameAction);
+
+//입력을 받아 행동하는 플레이어가 조종하는 팀
+#[derive(Resource)]
+pub struct PlayerControl{
+    pub team: Team
+}
+
+impl Default for PlayerControl{
+    fn default() -> Self {
+        Self { team: Team::BLUE }
+    }
+}
+
+pub fn setup_simulation(
+    mut commands: Commands,
+    res_map: Res<Map>,
+    res_seed: Res<MatchSeed>,
+    res_rules: Res<RulesRes>
+){
+    let sim = GameSim::new(&res_map, res_seed.0, &res_rules);
+    info!("match seed: {}", sim.state.seed());
+    commands.insert_resource(sim);
+}
+
+//Tab 키로 조종하는 팀을 남아있는 다음 팀으로 바꿈
+pub fn switch_control_team(
+    res_key: Res<Input<KeyCode>>,
+    res_sim: Res<GameSim>,
+    mut res_control: ResMut<PlayerControl>
+){
+    if res_key.just_pressed(KeyCode::Tab){
+        next_control_team(&res_sim, &mut res_control);
+    }
+}
+
+//조종하던 팀이 탈락해도 판이 이어진다면 남은 다음 팀으로 넘어감
+pub fn team_eliminated_event(
+    mut events_sim: EventReader<SimEvent>,
+    res_sim: Res<GameSim>,
+    mut res_control: ResMut<PlayerControl>
+){
+    for SimEvent(ev) in events_sim.iter(){
+        let GameEvent::TeamEliminated { team } = ev else {continue};
+        if *team == res_control.team && res_sim.state.outcome().is_none(){
+            next_control_team(&res_sim, &mut res_control);
+        }
+    }
+}
+
+fn next_control_team(res_sim: &GameSim, res_control: &mut PlayerControl){
+    let teams = res_sim.state.alive_teams();
+    let next = teams.iter()
+        .find(|team| **team > res_control.team)
+        .or(teams.first());
+    if let Some(team) = next{
+        res_control.team = *team;
+    }
+}
+
+pub fn apply_actions(
+    mut events_action: EventReader<ActionEvent>,
+    mut events_choice: EventWriter<CombinationChoice>,
+    mut res_sim: ResMut<GameSim>,
+    mut res_record: Option<ResMut<MatchRecord>>,
+    res_control: Res<PlayerControl>
+){
+    for ActionEvent(action) in events_action.iter(){
+        match (res_sim.state.apply(action.clone()), action) {
+            (Ok(()), _) => {
+                //적용된 행동만 기록함, 다시 보면 똑같이 적용됨
+                if let Some(record) = res_record.as_mut(){
+                    let tick = res_sim.ticks;
+                    record.0.record(tick, action.clone());
+                }
+            },
+            //조종하는 팀이 여러 기물 중 하나를 골라야 하면 메뉴를 띄움
+            (Err(ActionError::Ambiguous(choices)), GameAction::Combine { team, base, tiles, .. })
+                if *team == res_control.team => {
+                events_choice.send(CombinationChoice {
+                    team: *team,
+                    base: *base,
+                    tiles: tiles.clone(),
+                    choices
+                });
+            },
+            (Err(err), _) => debug!("action {:?} rejected: {:?}", action, err)
+        }
+    }
+}
+
+pub fn simulation_tick(
+    mut res_sim: ResMut<GameSim>,
+    res_time: Res<FixedTime>,
+    mut events_sim: EventWriter<SimEvent>
+){
+    res_sim.step(res_time.period.as_secs_f32(), &mut events_sim);
+}
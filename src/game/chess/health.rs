@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+
+use crate::game::graphics_3d::honeycomb::Map;
+
+use super::{game_sim::{GameSim, SimEvent}, simulation::GameEvent};
+
+//기물 모델의 자식으로 붙는 체력바, 실제 체력은 시뮬레이션이 관리하고 처음 피해를 입었을 때 만들어짐
+//크기와 높이는 모델 좌표 기준이라 모델과 같이 늘어나고 줄어듦
+#[derive(Component)]
+pub struct HealthBar;
+
+#[derive(Resource)]
+pub struct HealthRes{
+    mesh: Handle<Mesh>,
+    mat: Handle<StandardMaterial>
+}
+
+pub fn setup_health(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>
+){
+    commands.insert_resource(HealthRes{
+        mesh: meshes.add(Mesh::from(shape::Box::new(0.45, 0.06, 0.06))),
+        mat: materials.add(StandardMaterial{
+            base_color: Color::rgb(0.913, 0.647, 0.286),
+            unlit: true,
+            ..default()
+        })
+    });
+}
+
+//피해를 입은 기물은 남은 체력만큼 체력바를 줄임
+//체력이 0이 된 기물은 PieceDestroyed로 정리되므로 건드리지 않음
+pub fn health_damaged_event(
+    mut commands: Commands,
+    mut events_sim: EventReader<SimEvent>,
+    mut query_bar: Query<&mut Transform, With<HealthBar>>,
+    query_children: Query<&Children>,
+    res_health: Res<HealthRes>,
+    res_sim: Res<GameSim>,
+    res_map: Res<Map>
+){
+    for SimEvent(ev) in events_sim.iter(){
+        let GameEvent::Damaged { damage, hp } = ev else {continue};
+        let Some(piece) = res_sim.state.piece(damage.target) else {continue};
+        let Some(occupant) = res_map.occupant_of(piece.pos, piece.kind) else {continue};
+        let ratio = *hp as f32 / piece.kind.max_hp() as f32;
+        let bar = query_children.get(occupant.entity).ok()
+            .and_then(|children| children.iter().copied().find(|child| query_bar.contains(*child)));
+        match bar {
+            Some(bar) => query_bar.get_mut(bar).unwrap().scale.x = ratio,
+            None => {
+                let mut trans = Transform::from_xyz(0., 1.2, 0.);
+                trans.scale.x = ratio;
+                commands.entity(occupant.entity).with_children(|p|{
+                    p.spawn((
+                        PbrBundle{
+                            mesh: res_health.mesh.clone(),
+                            material: res_health.mat.clone(),
+                            transform: trans,
+                            ..Default::default()
+                        },
+                        HealthBar
+                    ));
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+
+use super::game_sim::{GameSim, PlayerControl};
+
+//조종하는 팀의 폰 수 / 상한
+#[derive(Component)]
+pub struct PawnCounterText;
+
+pub fn setup_hud(
+    mut commands: Commands
+){
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle{
+                font_size: 22.,
+                color: Color::WHITE,
+                ..default()
+            }
+        ).with_style(Style{
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.),
+            left: Val::Px(8.),
+            ..default()
+        }),
+        PawnCounterText
+    ));
+}
+
+pub fn pawn_counter_update(
+    res_sim: Res<GameSim>,
+    res_control: Res<PlayerControl>,
+    mut query_text: Query<&mut Text, With<PawnCounterText>>
+){
+    let Ok(mut text) = query_text.get_single_mut() else {return};
+    let count = res_sim.state.pawn_count(res_control.team);
+    let cap = res_sim.state.rules().pawn_cap;
+    text.sections[0].value = format!("Pawns {} / {}", count, cap);
+    text.sections[0].style.color = if count >= cap {Color::rgb(0.913, 0.647, 0.286)} else {Color::WHITE};
+}
@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+use hexx::Hex;
+
+use crate::game::graphics_3d::honeycomb::{Map, AimTarget};
+
+use super::picker::PickerRes;
+
+//보드 입력이 지금 무엇을 기다리는지, 입력 시스템들은 이 값에 맞는 것만 돌아감
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InteractionMode{
+    #[default]
+    Idle,
+    DragSelecting, //왼쪽 버튼을 누른 채 조합할 칸을 고르는 중
+    Aiming{
+        piece: Hex //조종하는 기물이 있는 칸
+    },
+    Menu, //여러 기물 중 하나를 고르는 메뉴가 떠 있음
+    Watching //리플레이를 보는 중, 보드 입력을 받지 않음
+}
+
+impl InteractionMode{
+    pub fn aiming(&self) -> Option<Hex>{
+        match self {
+            InteractionMode::Aiming { piece } => Some(*piece),
+            _ => None
+        }
+    }
+}
+
+//조준한 기물과 고른 방향이나 칸, 기물마다 자기 칸의 이벤트만 처리함
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AimEvent{
+    pub piece: Hex,
+    pub target: AimTarget
+}
+
+pub fn in_mode(mode: InteractionMode) -> impl FnMut(Res<InteractionMode>) -> bool + Clone{
+    move |res_mode: Res<InteractionMode>| *res_mode == mode
+}
+
+pub fn is_aiming(res_mode: Res<InteractionMode>) -> bool{
+    res_mode.aiming().is_some()
+}
+
+//Esc나 우클릭은 어느 모드에서든 하던 일을 버리고 Idle로 돌아감
+pub fn cancel_interaction(
+    mut commands: Commands,
+    res_key: Res<Input<KeyCode>>,
+    res_mouse: Res<Input<MouseButton>>,
+    mut res_mode: ResMut<InteractionMode>,
+    mut res_map: ResMut<Map>,
+    mut res_picker: ResMut<PickerRes>
+){
+    if !res_key.just_pressed(KeyCode::Escape) && !res_mouse.just_pressed(MouseButton::Right){
+        return;
+    }
+    match *res_mode {
+        InteractionMode::Idle | InteractionMode::Watching => return,
+        InteractionMode::DragSelecting => res_map.clear_selection(&mut commands),
+        InteractionMode::Aiming { .. } => res_map.clear_targets(&mut commands),
+        InteractionMode::Menu => res_picker.close(&mut commands)
+    }
+    *res_mode = InteractionMode::Idle;
+}
+
+//조준 중에 경로나 목표가 아닌 곳을 누르면 조준을 그만둠
+pub fn cancel_aim_outside(
+    mut commands: Commands,
+    res_mouse: Res<Input<MouseButton>>,
+    mut res_mode: ResMut<InteractionMode>,
+    mut res_map: ResMut<Map>
+){
+    if !res_mouse.just_pressed(MouseButton::Left){
+        return;
+    }
+    let on_target = res_map.hovered.and_then(|hex| res_map.aim_at(hex)).is_some();
+    if !on_target{
+        res_map.clear_targets(&mut commands);
+        *res_mode = InteractionMode::Idle;
+    }
+}
+
+//기물 칸을 누르면 조준을 시작함, 그 칸 하나만 누른 드래그는 조합 선택 대신 조준으로 바꿈
+pub fn begin_aim(commands: &mut Commands, res_mode: &mut InteractionMode, res_map: &mut Map, piece: Hex) -> bool{
+    match *res_mode {
+        InteractionMode::Idle => {},
+        InteractionMode::DragSelecting if res_map.selected_list.iter().all(|hex| *hex == piece) => {
+            res_map.clear_selection(commands);
+        },
+        _ => return false
+    }
+    *res_mode = InteractionMode::Aiming { piece };
+    true
+}
+
+//조준을 끝내고 Idle로 돌아감, 조준하던 기물 칸을 돌려줌
+pub fn finish_aim(commands: &mut Commands, res_mode: &mut InteractionMode, res_map: &mut Map) -> Option<Hex>{
+    let piece = res_mode.aiming()?;
+    res_map.clear_targets(commands);
+    *res_mode = InteractionMode::Idle;
+    Some(piece)
+}
+
+//표시한 경로나 목표 칸 위에서 왼쪽 버튼을 놓으면 조준을 확정함
+pub fn confirm_aim(
+    mut commands: Commands,
+    res_mouse: Res<Input<MouseButton>>,
+    mut res_mode: ResMut<InteractionMode>,
+    mut res_map: ResMut<Map>,
+    mut events_aim: EventWriter<AimEvent>
+){
+    if !res_mouse.just_released(MouseButton::Left){
+        return;
+    }
+    let Some(target) = res_map.hovered.and_then(|hex| res_map.aim_at(hex)) else {return};
+    if let Some(piece) = finish_aim(&mut commands, &mut res_mode, &mut res_map){
+        events_aim.send(AimEvent { piece, target });
+    }
+}
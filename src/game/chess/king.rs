@@ -0,0 +1,32 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+use super::{piece_visual::PieceVisual, simulation::{PieceId, PieceKind}};
+
+#[derive(Component)]
+pub struct King;
+
+//왕은 다른 기물보다 조금 크게 그리고 좌우로 천천히 흔들림
+impl PieceVisual for King{
+    const KIND: PieceKind = PieceKind::King;
+    const NAME: &'static str = "king";
+    const SCALE: f32 = 0.5;
+
+    fn new(_: PieceId) -> Self{
+        King
+    }
+
+    fn idle_curve() -> VariableCurve{
+        VariableCurve {
+            keyframe_timestamps: vec![0.,2.,4.],
+            keyframes: Keyframes::Rotation(
+                vec![
+                    Quat::from_euler(EulerRot::XYZ, 0., 0., PI/20. * -1.),
+                    Quat::from_euler(EulerRot::XYZ, 0., 0., PI/20.),
+                    Quat::from_euler(EulerRot::XYZ, 0., 0., PI/20. * -1.)
+                ]
+            )
+        }
+    }
+}
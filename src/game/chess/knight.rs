@@ -0,0 +1,166 @@
+use std::f32::consts::PI;
+
+use bevy::{prelude::*, ecs::system::EntityCommands};
+use bevy_mod_picking::prelude::{On, Click, Pointer, Listener, PointerButton};
+use hexx::Hex;
+
+use crate::game::graphics_3d::honeycomb::{Map, Honeycomb, AimTarget};
+
+use super::{piece_visual::PieceVisual, rules::RulesRes, interaction::{InteractionMode, AimEvent, begin_aim}, game_sim::{GameSim, SimEvent, PlayerControl, ActionEvent}, simulation::{PieceId, PieceKind, GameEvent, GameAction}};
+
+const GRENADE_ARC_HEIGHT: f32 = 6.; //칸 크기의 몇 배만큼 솟아오르는지
+
+//나이트만 쓰는 유탄 모델, 나이트 모델과 애니메이션은 PieceRes<Knight>
+#[derive(Resource)]
+pub struct KnightRes{
+    grenade_mesh: Handle<Mesh>
+}
+
+#[derive(Component)]
+pub struct Knight;
+
+//날아가는 유탄, 위치는 시뮬레이션의 진행도로 계산함
+#[derive(Component)]
+pub struct GrenadeShell{
+    id: PieceId,
+    from: Vec3,
+    to: Vec3
+}
+
+impl PieceVisual for Knight{
+    const KIND: PieceKind = PieceKind::Knight;
+    const NAME: &'static str = "knight";
+
+    fn new(_: PieceId) -> Self{
+        Knight
+    }
+
+    fn idle_curve() -> VariableCurve{
+        VariableCurve {
+            keyframe_timestamps: vec![0.,1.,2.,3.,4.],
+            keyframes: Keyframes::Rotation(
+                vec![
+                    Quat::IDENTITY,
+                    Quat::from_euler(EulerRot::XYZ, PI/8. * -1., 0., 0.),
+                    Quat::IDENTITY,
+                    Quat::from_euler(EulerRot::XYZ, PI/8. * -1., PI/2., 0.),
+                    Quat::IDENTITY
+                ]
+            )
+        }
+    }
+
+    fn on_spawn(tile: &mut EntityCommands){
+        tile.insert(On::<Pointer<Click>>::run(on_knight_click));
+    }
+}
+
+pub fn setup_asset_knight(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    res_rules: Res<RulesRes>
+){
+    commands.insert_resource(
+        KnightRes{
+            grenade_mesh: meshes.add(Mesh::from(shape::UVSphere{
+                radius: res_rules.hex_size * 0.3,
+                ..default()
+            }))
+        }
+    );
+}
+
+fn on_knight_click(
+    mut commands: Commands,
+    event: Listener<Pointer<Click>>,
+    mut res_map: ResMut<Map>,
+    mut res_mode: ResMut<InteractionMode>,
+    res_sim: Res<GameSim>,
+    res_control: Res<PlayerControl>
+){
+    if event.button == PointerButton::Primary{
+        let base_tile = res_map.entities_forentity[&event.target];
+        let Some(knight) = res_map.occupant_of(base_tile, PieceKind::Knight) else {return};
+        let team = knight.team;
+        if team != res_control.team {return}
+        if !begin_aim(&mut commands, &mut res_mode, &mut res_map, base_tile) {return}
+        //아군 기물이 있는 칸은 조준하지 않음
+        let targets: Vec<Hex> = res_sim.state.throw_targets(base_tile).into_iter()
+            .filter(|target| res_map.team_at(*target) != Some(team))
+            .collect();
+        res_map.offer_tiles(&mut commands, targets);
+    }
+}
+
+pub fn knight_aim_event(
+    mut events_aim: EventReader<AimEvent>,
+    mut events_action: EventWriter<ActionEvent>,
+    res_map: Res<Map>,
+    res_control: Res<PlayerControl>
+){
+    for ev in events_aim.iter(){
+        let AimTarget::Tile(target) = ev.target else {continue};
+        if res_map.occupant_of(ev.piece, PieceKind::Knight).is_none() {continue}
+        events_action.send(ActionEvent(GameAction::Throw { team: res_control.team, from: ev.piece, target }));
+    }
+}
+
+pub fn knight_throw_event(
+    mut commands: Commands,
+    mut events_sim: EventReader<SimEvent>,
+    res_knight: Res<KnightRes>,
+    res_map: Res<Map>,
+    res_sim: Res<GameSim>,
+    query_transform: Query<&GlobalTransform, With<Honeycomb>>
+){
+    for SimEvent(ev) in events_sim.iter(){
+        let GameEvent::Thrown { id, from, target } = ev else {continue};
+        //같은 프레임에 이미 착탄한 유탄은 만들지 않음
+        if res_sim.state.grenade(*id).is_none() {continue}
+        let from_trans = query_transform.get(res_map.entities[from]).unwrap().translation();
+        let to_trans = query_transform.get(res_map.entities[target]).unwrap().translation();
+        let mat = res_map.team_mat(*from);
+        commands.spawn((
+            PbrBundle{
+                mesh: res_knight.grenade_mesh.clone(),
+                material: mat,
+                transform: Transform::from_translation(from_trans),
+                ..Default::default()
+            },
+            GrenadeShell{
+                id: *id,
+                from: from_trans,
+                to: to_trans
+            }
+        ));
+    }
+}
+
+pub fn grenade_exploded_event(
+    mut commands: Commands,
+    mut events_sim: EventReader<SimEvent>,
+    query_grenade: Query<(Entity, &GrenadeShell)>
+){
+    for SimEvent(ev) in events_sim.iter(){
+        let GameEvent::GrenadeExploded { id, .. } = ev else {continue};
+        for (entity, shell) in query_grenade.iter(){
+            if shell.id == *id{
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+pub fn grenade_flying(
+    mut query_grenade: Query<(&GrenadeShell, &mut Transform)>,
+    res_sim: Res<GameSim>,
+    res_rules: Res<RulesRes>
+){
+    for (shell, mut trans) in query_grenade.iter_mut(){
+        //착탄하면 grenade_exploded_event가 치움
+        let Some(grenade) = res_sim.state.grenade(shell.id) else {continue};
+        let t = grenade.progress();
+        let height = res_rules.hex_size * (1. / 3. + GRENADE_ARC_HEIGHT * 4. * t * (1. - t));
+        trans.translation = shell.from.lerp(shell.to, t) + Vec3::Y * height;
+    }
+}
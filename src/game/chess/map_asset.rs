@@ -0,0 +1,86 @@
+use bevy::{prelude::*, asset::{AssetLoader, LoadContext, LoadedAsset, LoadState}, reflect::{TypeUuid, TypePath}, utils::BoxedFuture};
+
+use super::{pawn::PawnSetup, rules::RulesRes, simulation::MapFile};
+
+//손으로 만든 보드 에셋, 로드할 때 검사를 통과한 맵만 들어옴
+#[derive(Debug, TypeUuid, TypePath)]
+#[uuid = "0c7e4a9d-5f21-4b63-8e1a-d4b9f62c3e17"]
+pub struct BoardMap(pub MapFile);
+
+#[derive(Default)]
+pub struct MapLoader;
+
+impl AssetLoader for MapLoader{
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let map: MapFile = serde_json::from_slice(bytes)?;
+            map.validate()?;
+            load_context.set_default_asset(LoadedAsset::new(BoardMap(map)));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["map.json"]
+    }
+}
+
+//규칙에 맵 파일이 없으면 None
+#[derive(Resource)]
+pub struct MapHandle(pub Option<Handle<BoardMap>>);
+
+//이번 판의 보드, setup_grid와 test_setup이 이것으로 보드를 만듦
+#[derive(Resource, Deref)]
+pub struct BoardRes(pub MapFile);
+
+pub fn setup_map(
+    mut commands: Commands,
+    assets_server: Res<AssetServer>,
+    res_rules: Res<RulesRes>
+){
+    let handle = res_rules.map.as_ref().map(|path| assets_server.load(path.as_str()));
+    commands.insert_resource(MapHandle(handle));
+}
+
+//보드를 미리 넣어두었으면 (리플레이) 맵 파일을 기다리지 않고 보드를 만들기 시작함
+pub fn board_ready(
+    mut pawn_setup_state: ResMut<NextState<PawnSetup>>
+){
+    pawn_setup_state.set(PawnSetup::Befor);
+}
+
+//맵이 로드되면 보드를 만들기 시작함, 로드에 실패하면 규칙의 보드 모양을 씀
+pub fn map_loaded_event(
+    mut commands: Commands,
+    assets_server: Res<AssetServer>,
+    res_maps: Res<Assets<BoardMap>>,
+    res_handle: Res<MapHandle>,
+    res_rules: Res<RulesRes>,
+    mut pawn_setup_state: ResMut<NextState<PawnSetup>>
+){
+    let map = match &res_handle.0 {
+        None => MapFile::from_shape(&res_rules.board, &res_rules.obstacles),
+        Some(handle) => {
+            if let Some(map) = res_maps.get(handle){
+                match map.0.check_players(res_rules.players) {
+                    Ok(()) => map.0.clone(),
+                    Err(err) => {
+                        error!("map {:?} does not fit {} players, using the board from the rules: {}", res_rules.map, res_rules.players, err);
+                        MapFile::from_shape(&res_rules.board, &res_rules.obstacles)
+                    }
+                }
+            }else if assets_server.get_load_state(handle) == LoadState::Failed{
+                error!("map {:?} could not be loaded, using the board from the rules", res_rules.map);
+                MapFile::from_shape(&res_rules.board, &res_rules.obstacles)
+            }else{
+                return;
+            }
+        }
+    };
+    commands.insert_resource(BoardRes(map));
+    pawn_setup_state.set(PawnSetup::Befor);
+}
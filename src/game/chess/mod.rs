@@ -1,13 +1,32 @@
 use bevy::prelude::*;
 
-use self::{pawn::{setup_asset_pawn, test_setup, spawn_pawn_timer, spawn_pawn_event, PawnSpawn, PawnSetup, pawn_spawn_anim_is_end, pawn_action_anim_is_end, selected_event, pawn_combination_is_end, OtherSpawn}, bishop::{setup_asset_bishop, bishop_spawn_event, bishop_spawn_anim_is_end, cancel_path, bishop_attacking}};
+use self::{pawn::{setup_asset_pawn, test_setup, spawn_pawn_event, PawnSetup, pawn_spawn_anim_is_end, pawn_action_anim_is_end, selected_event, pawn_combination_is_end, OtherSpawn, combination_event, pawn_destroyed_event}, bishop::{Bishop, bishop_aim_event, bishop_attacking, bishop_launch_event}, rook::{Rook, setup_asset_rook, rook_block_event, rook_block_anim_is_end}, knight::{Knight, setup_asset_knight, knight_aim_event, knight_throw_event, grenade_exploded_event, grenade_flying}, queen::{Queen, setup_asset_queen, queen_aim_event, queen_fire_event, beam_flying}, piece_visual::{setup_asset_piece, piece_spawn_event, piece_spawn_anim_is_end, piece_destroyed_event}, game_sim::{GameSim, SimEvent, ActionEvent, PlayerControl, MatchSeed, SIM_TIMESTEP, setup_simulation, simulation_tick, apply_actions, switch_control_team, team_eliminated_event}, replay::{MatchRecord, Playback, replay_arg, load_replay, setup_recording, setup_playback, save_replay, setup_replay_hud, replay_controls, replay_rewind, replay_playback, replay_hud_update}, king::King, outcome::{GameOutcome, game_over_event, spawn_result_screen}, recipe_asset::{RecipeBook, RecipeLoader, setup_recipe, recipe_loaded_event}, preview::{setup_preview, combination_preview}, picker::{CombinationChoice, PickerRes, open_picker, picker_button_click}, interaction::{InteractionMode, AimEvent, in_mode, is_aiming, cancel_interaction, cancel_aim_outside, confirm_aim}, health::{setup_health, health_damaged_event}, rules::{RulesRes, RulesAsset, RulesLoader, RulesHandle, setup_rules, rules_loaded_event}, hud::{setup_hud, pawn_counter_update}, map_asset::{BoardMap, BoardRes, MapLoader, MapHandle, setup_map, map_loaded_event, board_ready}};
+use crate::game::graphics_3d::honeycomb::setup_grid;
 
 mod pawn;
 mod bishop;
+mod rook;
+mod knight;
+mod queen;
+mod king;
+mod piece_visual;
+mod outcome;
+mod recipe_asset;
+mod preview;
+mod picker;
+pub mod interaction;
+mod health;
+mod hud;
+pub mod map_asset;
+mod game_sim;
+mod replay;
+pub mod rules;
+pub mod simulation;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[derive(SystemSet)]
 enum ScadulSet {
+    Vacate, //칸을 비우는 시스템들, 같은 프레임에 빈 칸에 새 기물이 올라올 수 있어서 Spawn 보다 먼저
     Spawn, //insert가 들어가거나 하는 형태의 시스템들
     SetUp  //리소스가 생성되는 형태의 시스템들
 }
@@ -16,34 +35,149 @@ pub struct ChessGamePlugin;
 
 impl Plugin for ChessGamePlugin {
     fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<Playback>(){
+            if let Some(path) = replay_arg(){
+                match load_replay(&path) {
+                    Ok(replay) => {app.insert_resource(Playback::new(replay));},
+                    Err(err) => error!("{} could not be loaded, starting a new game: {}", path, err)
+                }
+            }
+        }
+        //리플레이는 기록한 시드, 규칙, 보드로 같은 판을 만들고 보드 입력은 받지 않음
+        if let Some(replay) = app.world.get_resource::<Playback>().map(|playback| playback.replay.clone()){
+            app.insert_resource(MatchSeed(Some(replay.seed)));
+            app.insert_resource(RulesRes(replay.rules));
+            app.insert_resource(BoardRes(replay.board));
+            app.insert_resource(InteractionMode::Watching);
+        }
         app
         .add_state::<PawnSetup>()
-        .add_event::<PawnSpawn>()
+        .add_state::<GameOutcome>()
+        .init_resource::<PlayerControl>()
+        .init_resource::<MatchSeed>()
+        .insert_resource(FixedTime::new_from_secs(SIM_TIMESTEP))
+        .add_asset::<RecipeBook>()
+        .init_asset_loader::<RecipeLoader>()
+        .add_asset::<BoardMap>()
+        .init_asset_loader::<MapLoader>()
+        .add_asset::<RulesAsset>()
+        .init_asset_loader::<RulesLoader>()
+        .add_event::<SimEvent>()
         .add_event::<OtherSpawn>()
+        .add_event::<CombinationChoice>()
+        .add_event::<AimEvent>()
+        .add_event::<ActionEvent>()
+        .init_resource::<PickerRes>()
+        .init_resource::<InteractionMode>()
+        .configure_set(Update, ScadulSet::Vacate.before(ScadulSet::Spawn))
+        //규칙을 미리 넣어두지 않았으면 규칙 파일, 그 다음 규칙의 맵 파일 순서로 기다림
+        .add_systems(Startup, setup_rules.run_if(not(resource_exists::<RulesRes>())))
+        .add_systems(Update, (
+            rules_loaded_event.run_if(resource_exists::<RulesHandle>()).run_if(not(resource_exists::<RulesRes>())),
+            setup_map.run_if(resource_exists::<RulesRes>()).run_if(not(resource_exists::<MapHandle>())).run_if(not(resource_exists::<BoardRes>())),
+            map_loaded_event.run_if(resource_exists::<MapHandle>()),
+            board_ready.run_if(resource_exists::<BoardRes>()),
+        ).chain().run_if(in_state(PawnSetup::Loading)))
+        .add_systems(OnExit(PawnSetup::Loading), setup_grid)
         .add_systems(OnEnter(PawnSetup::Befor),(
-            setup_asset_bishop,
+            setup_simulation,
+            setup_recipe,
+            setup_preview,
+            setup_hud,
+            setup_health,
+            setup_asset_piece::<Bishop>,
+            setup_asset_piece::<Rook>,
+            setup_asset_piece::<Knight>,
+            setup_asset_piece::<Queen>,
+            setup_asset_piece::<King>,
+            setup_asset_rook,
+            setup_asset_knight,
+            setup_asset_queen,
             setup_asset_pawn,
         ).chain().in_set(ScadulSet::SetUp))
         .add_systems(OnEnter(PawnSetup::After),(
             test_setup,
-        ).before(ScadulSet::SetUp))
+            setup_recording.run_if(not(resource_exists::<Playback>())),
+            setup_playback.run_if(resource_exists::<Playback>()),
+            setup_replay_hud.run_if(resource_exists::<Playback>()),
+        ).chain().before(ScadulSet::SetUp))
+        .add_systems(OnEnter(GameOutcome::Victory), spawn_result_screen)
+        .add_systems(OnEnter(GameOutcome::Defeat), spawn_result_screen)
+        .add_systems(OnEnter(GameOutcome::Draw), spawn_result_screen)
         .add_systems(Update, (
             (
-                cancel_path,
-            ).before(ScadulSet::Spawn),
+                (
+                    switch_control_team,
+                    cancel_interaction,
+                    cancel_aim_outside.run_if(is_aiming),
+                    confirm_aim.run_if(is_aiming),
+                    bishop_aim_event,
+                    knight_aim_event,
+                    queen_aim_event,
+                    selected_event,
+                    picker_button_click.run_if(in_mode(InteractionMode::Menu)),
+                ).chain().run_if(in_state(GameOutcome::Playing)),
+                //리플레이는 판이 끝난 뒤에도 되감을 수 있음
+                (
+                    replay_controls,
+                    replay_rewind,
+                    replay_playback,
+                ).chain().run_if(resource_exists::<Playback>()).run_if(in_state(PawnSetup::After)),
+                apply_actions,
+                open_picker,
+                recipe_loaded_event.run_if(not(resource_exists::<Playback>())),
+            ).chain().before(ScadulSet::Spawn),
             (
-                spawn_pawn_timer,
                 spawn_pawn_event,
                 pawn_spawn_anim_is_end,
                 pawn_action_anim_is_end,
-                bishop_spawn_event,
-                bishop_spawn_anim_is_end,
-                selected_event
+                piece_spawn_event::<Bishop>,
+                piece_spawn_event::<Rook>,
+                piece_spawn_event::<Knight>,
+                piece_spawn_event::<Queen>,
+                piece_spawn_event::<King>,
+                piece_spawn_anim_is_end::<Bishop>,
+                piece_spawn_anim_is_end::<Rook>,
+                piece_spawn_anim_is_end::<Knight>,
+                piece_spawn_anim_is_end::<Queen>,
+                piece_spawn_anim_is_end::<King>,
+            ).in_set(ScadulSet::Spawn),
+            (
+                rook_block_event,
+                rook_block_anim_is_end,
+                knight_throw_event,
+                grenade_exploded_event,
+                queen_fire_event,
+            ).in_set(ScadulSet::Spawn),
+            (
+                combination_event,
+                bishop_launch_event,
+                pawn_destroyed_event,
+                piece_destroyed_event::<Bishop>,
+                piece_destroyed_event::<Rook>,
+                piece_destroyed_event::<Knight>,
+                piece_destroyed_event::<Queen>,
+                piece_destroyed_event::<King>,
+            ).in_set(ScadulSet::Vacate),
+            (
+                health_damaged_event,
+                team_eliminated_event,
+                game_over_event,
             ).in_set(ScadulSet::Spawn),
             (
                 pawn_combination_is_end,
-                bishop_attacking
-            ).before(ScadulSet::Spawn)
-        ));
+                bishop_attacking,
+                grenade_flying,
+                beam_flying,
+                combination_preview,
+                pawn_counter_update,
+                save_replay.run_if(resource_exists::<MatchRecord>()),
+                replay_hud_update.run_if(resource_exists::<Playback>()),
+            ).after(ScadulSet::Spawn)
+        ).run_if(resource_exists::<GameSim>()))
+        .add_systems(FixedUpdate, simulation_tick
+            .run_if(resource_exists::<GameSim>())
+            .run_if(in_state(GameOutcome::Playing))
+            .run_if(not(resource_exists::<Playback>())));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+
+use super::{game_sim::{SimEvent, PlayerControl}, simulation::{GameEvent, Outcome}};
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
+pub enum GameOutcome {
+    #[default]
+    Playing,
+    Victory,
+    Defeat,
+    Draw
+}
+
+#[derive(Component)]
+pub struct ResultScreen;
+
+//승패는 조종하고 있는 팀 기준
+pub fn game_over_event(
+    mut events_sim: EventReader<SimEvent>,
+    mut next_state: ResMut<NextState<GameOutcome>>,
+    res_control: Res<PlayerControl>
+){
+    for SimEvent(ev) in events_sim.iter(){
+        let GameEvent::GameOver { outcome } = ev else {continue};
+        next_state.set(match outcome {
+            Outcome::Winner(team) if *team == res_control.team => GameOutcome::Victory,
+            Outcome::Winner(_) => GameOutcome::Defeat,
+            Outcome::Draw => GameOutcome::Draw
+        });
+    }
+}
+
+pub fn spawn_result_screen(
+    mut commands: Commands,
+    state: Res<State<GameOutcome>>
+){
+    let (text, color) = match state.get(){
+        GameOutcome::Victory => ("VICTORY", Color::rgb(0.286, 0.513, 0.913)),
+        GameOutcome::Defeat => ("DEFEAT", Color::rgb(0.913, 0.286, 0.286)),
+        GameOutcome::Draw => ("DRAW", Color::WHITE),
+        GameOutcome::Playing => return
+    };
+    commands.spawn((
+        NodeBundle{
+            style: Style{
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: Color::rgba(0., 0., 0., 0.6).into(),
+            ..default()
+        },
+        ResultScreen
+    )).with_children(|p|{
+        p.spawn(TextBundle::from_section(
+            text,
+            TextStyle{
+                font_size: 60.,
+                color,
+                ..default()
+            }
+        ));
+    });
+}
@@ -1,13 +1,15 @@
 use std::f32::consts::PI;
 
-use bevy::{prelude::*, utils::{HashSet, HashMap}};
+use bevy::prelude::*;
 use hexx::Hex;
-use rand::Rng;
-use crate::game::graphics_3d::honeycomb::{Map, MAP_RADIUS, HEX_SIZE, HexSelecedEndEvent, Honeycomb};
+use crate::game::graphics_3d::honeycomb::{Map, HexSelecedEndEvent, Honeycomb, Occupant};
+
+use super::{piece_visual::spawn_curve, rules::RulesRes, map_asset::BoardRes, game_sim::{GameSim, SimEvent, ActionEvent, PlayerControl}, simulation::{PieceId, PieceKind, GameEvent, GameAction, GameState, MapFile, SpawnCategory, Team}};
 
 #[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
 pub enum PawnSetup{
     #[default]
+    Loading, //보드 맵을 기다리는 중
     Befor,
     After
 }
@@ -16,39 +18,22 @@ pub enum PawnSetup{
 pub struct PawnRes{
     //scene: Handle<Scene>,
     mesh: Handle<Mesh>,
-    pawn_list: HashMap<Hex, Entity>,
-    blue_pawn_list: HashSet<Hex>,
-    red_pawn_list: HashSet<Hex>,
     spawn_animation: (Handle<AnimationClip>, Name),
     idle_animation: (Handle<AnimationClip>, Name),
     action_animation: (Handle<AnimationClip>, Name)
 }
 
-pub enum SpawnCategory{
-    Bishop,
-    Rook,
-    Knight,
-    Qean
-}
-
-#[derive(Event)]
-pub struct PawnSpawn{
-    blue_team: bool,
-    pos: Hex,
-    entity: Entity
-}
 #[derive(Event)]
 pub struct OtherSpawn{
-    pub blue_team: bool,
+    pub id: Option<PieceId>,
+    pub team: Team,
     pub base_pos: Hex,
     pub category: SpawnCategory
 }
 
 #[derive(Component)]
 pub struct Pawn{
-    spawn_timer: Timer,
-    blue_team: bool,
-    pos: Hex
+    pub id: PieceId
 }
 #[derive(Component)]
 pub struct SpawnAnimToggle;
@@ -67,22 +52,11 @@ pub fn setup_asset_pawn(
     assets_server: Res<AssetServer>,
     mut pawn_setup_state: ResMut<NextState<PawnSetup>>,
     mut animations: ResMut<Assets<AnimationClip>>,
+    res_rules: Res<RulesRes>
 ){
     let spawn_anim = Name::new("pawn_spawn");
     let mut spawn_animation = AnimationClip::default();
-    spawn_animation.add_curve_to_path(
-        EntityPath{parts: vec![spawn_anim.clone()]}, 
-        VariableCurve { 
-            keyframe_timestamps: vec![0.,0.6,1.2], 
-            keyframes: Keyframes::Translation(
-                vec![
-                    Vec3::new(0., HEX_SIZE * -2., 0.),
-                    Vec3::new(0., HEX_SIZE * 1.2, 0.),
-                    Vec3::new(0., HEX_SIZE / 3., 0.)
-                ]
-            ) 
-        }
-    );
+    spawn_animation.add_curve_to_path(EntityPath{parts: vec![spawn_anim.clone()]}, spawn_curve(res_rules.hex_size));
     let idle_anim = Name::new("pawn_idle");
     let mut idle_animation = AnimationClip::default();
     idle_animation.add_curve_to_path(
@@ -124,14 +98,10 @@ pub fn setup_asset_pawn(
     let spawn_animation_handle = animations.add(spawn_animation);
     let idle_animation_handle = animations.add(idle_animation);
     let action_animation_handle = animations.add(action_animation);
-    let map_size = (MAP_RADIUS * MAP_RADIUS) as usize;
     commands.insert_resource(
         PawnRes{
             //scene: assets_server.load("pawn.glb#Scene0"),
             mesh: assets_server.load("pawn.glb#Mesh0/Primitive0"),
-            blue_pawn_list: HashSet::with_capacity(map_size),
-            red_pawn_list:HashSet::with_capacity(map_size),
-            pawn_list: HashMap::with_capacity(map_size),
             spawn_animation: (spawn_animation_handle, spawn_anim),
             idle_animation:(idle_animation_handle, idle_anim),
             action_animation:(action_animation_handle, action_anim)
@@ -143,14 +113,13 @@ pub fn setup_asset_pawn(
 fn spawn_pawn(
     parent: &mut ChildBuilder,
     pawn_mesh: Handle<Mesh>,
-    select_team: bool,
-    pos: Hex,
+    id: PieceId,
     mat: Handle<StandardMaterial>,
     player: AnimationPlayer,
+    rules: &RulesRes
 ) -> Entity{
-    let mut rng = rand::thread_rng();
-    let mut trans = Transform::from_xyz(0., HEX_SIZE/3. , 0.);
-    trans.scale = Vec3{x:0.4, y:0.4, z:0.4};
+    let mut trans = Transform::from_xyz(0., rules.hex_size/3. , 0.);
+    trans.scale = Vec3::splat(0.4 * rules.model_scale());
     parent.spawn((
         // SceneBundle{
         //     scene: pawn_scen,
@@ -164,9 +133,7 @@ fn spawn_pawn(
             ..Default::default()
         },
         Pawn{
-            spawn_timer: Timer::from_seconds(rng.gen_range(2.8..3.2), TimerMode::Repeating),
-            blue_team: select_team,
-            pos
+            id
         },
         player,
         Name::new("pawn_spawn"),
@@ -175,24 +142,6 @@ fn spawn_pawn(
     ).id()
 }
 
-pub fn spawn_pawn_timer(
-    mut query: Query<(&mut Pawn, Entity)>,
-    res_time: Res<Time>,
-    mut events_pawn_spawn: EventWriter<PawnSpawn>
-){
-    let time = res_time.delta();
-    for (mut p, entity) in query.iter_mut(){
-        p.spawn_timer.tick(time);
-        if p.spawn_timer.finished(){
-            events_pawn_spawn.send(PawnSpawn{
-                blue_team: p.blue_team,
-                pos: p.pos,
-                entity
-            });
-        }
-    }
-}
-
 pub fn pawn_spawn_anim_is_end(
     mut commands: Commands,
     mut query_player: Query<(&mut AnimationPlayer, Entity), (With<SpawnAnimToggle>, With<Pawn>)>,
@@ -225,13 +174,14 @@ pub fn pawn_action_anim_is_end(
 pub fn pawn_combination_is_end(
     mut commands: Commands,
     mut query_pawns: Query<(&mut AnimationPlayer, &mut CombinationTarget, &mut Transform, &GlobalTransform, Entity)>,
-    res_time: Res<Time>
+    res_time: Res<Time>,
+    res_rules: Res<RulesRes>
 ){
     for (mut player,mut combi, mut trans, gt,entity) in query_pawns.iter_mut(){
         player.pause();
         combi.time += res_time.delta_seconds();
         let mut move_tarns = (combi.trans.translation() - gt.translation()) * combi.time * 2.5;
-        move_tarns.y = HEX_SIZE / 3. + f32::sin(combi.time * PI*2.) / 4.;
+        move_tarns.y = res_rules.hex_size / 3. + f32::sin(combi.time * PI*2.) / 4.;
         trans.translation = move_tarns;
 
         if combi.time >= 0.5{
@@ -242,334 +192,144 @@ pub fn pawn_combination_is_end(
 
 pub fn spawn_pawn_event(
     mut commands: Commands,
-    mut res_pawn: ResMut<PawnRes>,
+    res_pawn: Res<PawnRes>,
     mut res_map: ResMut<Map>,
-    mut events_pawn_spawn: EventReader<PawnSpawn>,
+    res_sim: Res<GameSim>,
+    res_rules: Res<RulesRes>,
+    mut events_sim: EventReader<SimEvent>,
     mut query_player: Query<&mut AnimationPlayer, With<Pawn>>
 ){
-    'eventing: for ev in events_pawn_spawn.iter(){
-        let mat: Handle<StandardMaterial>;
-        let hex_pos:Hex;
-        let mut spawn_list = vec![
-            [0,1],
-            [1,0],
-            [-1,1],
-            [0,-1],
-            [-1,0],
-            [1,-1],
-        ];
-        let mut rng = rand::thread_rng();
-        loop {
-            let index = rng.gen_range(0..spawn_list.len());
-            let _pos = Hex{x: ev.pos.x + spawn_list[index][0], y: ev.pos.y + spawn_list[index][1]};
-            let entity = res_map.entities.get(&_pos);
-            if entity.is_some(){
-                let ent = entity.unwrap();
-                if !res_map.blue_entities.contains(ent) && !res_map.red_entities.contains(ent){
-                    hex_pos = _pos;
-                    break;
-                }else{
-                    spawn_list.remove(index);
-                }
-            }else{
-                spawn_list.remove(index);
-            }
-
-            if spawn_list.is_empty(){
-                continue 'eventing;
-            }
-        }
-        let Some(mut pawn) = commands.get_entity(ev.entity) else {continue};
+    for SimEvent(ev) in events_sim.iter(){
+        let GameEvent::PieceSpawned { id, kind: PieceKind::Pawn, team, pos, parent } = ev else {continue};
+        //같은 프레임에 이미 사라진 폰은 만들지 않음
+        if res_sim.state.piece(*id).is_none() {continue}
+        let hex_pos = *pos;
         let spawn_entity = res_map.entities[&hex_pos];
-        if ev.blue_team{
-            res_pawn.blue_pawn_list.insert(hex_pos);
-            mat = res_map.blue_mat.clone();
-            res_map.blue_entities.insert(spawn_entity);
-        }else{
-            res_pawn.red_pawn_list.insert(hex_pos);
-            mat = res_map.red_mat.clone();
-            res_map.red_entities.insert(spawn_entity);
+        let mat = res_map.team_color(*team);
+
+        //증식시킨 폰은 액션 애니메이션
+        let spawner = parent
+            .and_then(|p| res_sim.state.piece(p))
+            .and_then(|p| res_map.occupant_of(p.pos, PieceKind::Pawn))
+            .map(|occupant| occupant.entity);
+        if let Some(spawner_entity) = spawner{
+            if let Ok(mut spawner) = query_player.get_mut(spawner_entity){
+                spawner.play(res_pawn.action_animation.0.clone());
+                commands.entity(spawner_entity).insert((
+                    ActionAnimToggle,
+                    res_pawn.action_animation.1.clone()
+                ));
+            }
         }
-
-        pawn.insert(ActionAnimToggle);
-        let mut spawner = query_player.get_mut(ev.entity).unwrap();
-        spawner.play(res_pawn.action_animation.0.clone());
-        pawn.insert(res_pawn.action_animation.1.clone());
         
         let mut player = AnimationPlayer::default();
         player.play(res_pawn.spawn_animation.0.clone());
-        if !res_map.path_list.contains(&hex_pos){
+        if res_map.aim_at(hex_pos).is_none(){
             commands.entity(spawn_entity).insert(mat.clone());
         }
+        let mut entity = Entity::from_bits(0);
         commands.entity(spawn_entity).with_children(|p|{
             let mesh = res_pawn.mesh.clone();
-            res_pawn.pawn_list.insert(hex_pos, spawn_pawn(p,mesh,ev.blue_team, hex_pos, mat, player));
+            entity = spawn_pawn(p, mesh, *id, mat, player, &res_rules);
         });
+        res_map.occupy(hex_pos, Occupant { team: *team, kind: PieceKind::Pawn, entity });
     }
 }
 
 pub fn test_setup(
-    mut commands: Commands,
-    mut res_pawn: ResMut<PawnRes>,
-    mut res_map: ResMut<Map>
+    mut res_sim: ResMut<GameSim>,
+    res_board: Res<BoardRes>
 ){
-    {
-        let pos = (MAP_RADIUS) as i32;
-        let hex_pos = Hex{x:pos, y:0};
-        let entity = res_map.entities[&hex_pos];
-        res_pawn.blue_pawn_list.insert(hex_pos);
-        let mat = res_map.blue_mat.clone();
-        res_map.blue_entities.insert(entity);
-        commands.entity(entity).insert(mat.clone());
-        let spawn_entity = res_map.entities[&hex_pos];
-        let mut player = AnimationPlayer::default();
-        player.play(res_pawn.spawn_animation.0.clone());
-        commands.entity(spawn_entity).with_children(|p|{
-            let mesh = res_pawn.mesh.clone();
-            res_pawn.pawn_list.insert(hex_pos, spawn_pawn(p, mesh ,true, hex_pos, mat,player));
-        });
+    place_start_pieces(&mut res_sim.state, &res_board);
+    //리플레이의 시간은 기물을 놓은 뒤부터 셈
+    res_sim.ticks = 0;
+}
+
+//맵의 기물과 팀마다 왕, 그 안쪽에 폰 하나를 놓음
+pub fn place_start_pieces(state: &mut GameState, board: &MapFile){
+    //맵에 시작 위치가 없으면 보드 모양에 맞춰 정함
+    let starts: Vec<(Team, Hex)> = if board.starts.is_empty(){
+        state.start_positions(state.rules().players).into_iter()
+            .map(|(team, king, _)| (team, king))
+            .collect()
+    }else{
+        board.starts.iter()
+            .map(|start| (start.team, Hex::new(start.x, start.y)))
+            .collect()
+    };
+    for piece in board.pieces.iter(){
+        state.place(piece.kind, piece.team, Hex::new(piece.x, piece.y));
     }
-    {
-        let pos = (MAP_RADIUS) as i32;
-        let hex_pos = Hex{x:pos * -1, y:0};
-        let entity = res_map.entities[&hex_pos];
-        res_pawn.red_pawn_list.insert(hex_pos);
-        let mat = res_map.red_mat.clone();
-        res_map.red_entities.insert(entity);
-        commands.entity(entity).insert(mat.clone());
-        let spawn_entity = res_map.entities[&hex_pos];
-        let mut player = AnimationPlayer::default();
-        player.play(res_pawn.spawn_animation.0.clone());
-        commands.entity(spawn_entity).with_children(|p|{
-            let mesh = res_pawn.mesh.clone();
-            res_pawn.pawn_list.insert(hex_pos, spawn_pawn(p, mesh ,false, hex_pos, mat,player));
-        });
+    for (team, king) in starts{
+        state.place(PieceKind::King, team, king);
+        if let Some(pawn) = state.start_pawn(king){
+            state.place(PieceKind::Pawn, team, pawn);
+        }
     }
 }
 
 pub fn selected_event(
-    mut commands: Commands,
     mut events_selected: EventReader<HexSelecedEndEvent>,
+    mut events_action: EventWriter<ActionEvent>,
+    res_control: Res<PlayerControl>
+){
+    for ev in events_selected.iter(){
+        events_action.send(ActionEvent(GameAction::Combine {
+            team: res_control.team,
+            base: ev.base_seleced,
+            tiles: ev.seleced_list.clone(),
+            category: None
+        }));
+    }
+}
+
+pub fn combination_event(
+    mut commands: Commands,
+    mut events_sim: EventReader<SimEvent>,
     mut events_other: EventWriter<OtherSpawn>,
-    mut res_pawn: ResMut<PawnRes>,
     mut res_map: ResMut<Map>,
     query_transform: Query<&GlobalTransform, With<Honeycomb>>
 ){
-    for ev in events_selected.iter(){
-        let base_hex = ev.base_seleced;
-        let mut hex_list: HashSet<Hex> = HashSet::with_capacity(ev.seleced_list.capacity());
-        for hex in ev.seleced_list.iter(){
-            if res_pawn.blue_pawn_list.contains(hex){
-                hex_list.insert(hex.clone());
-            }
-        }
-        if bishop_patton(base_hex, hex_list.clone()){
-            let base_tile = res_map.entities[&base_hex];
-            for hex in hex_list.iter(){
-                let pawn = res_pawn.pawn_list.remove(hex).unwrap();
-                res_pawn.blue_pawn_list.remove(hex);
-                let tile = res_map.entities[hex];
-                if base_tile != tile{
-                    res_map.blue_entities.remove(&tile);
-                    commands.entity(tile).insert(
-                        res_map.default_mat.clone()
-                    );
-                }
-                commands.entity(pawn).insert(
-                    CombinationTarget{
-                        trans: query_transform.get(base_tile).unwrap().clone(),
-                        time: 0.
-                    }
-                );
-            }
-            //이벤트 발생
-            events_other.send(OtherSpawn { 
-                blue_team: true,
-                base_pos: base_hex, 
-                category: SpawnCategory::Bishop
-            });
-        }
-        else if rook_patton(base_hex, hex_list.clone()){
-            let base_tile = res_map.entities[&base_hex];
-            for hex in hex_list.iter(){
-                let pawn = res_pawn.pawn_list.remove(hex).unwrap();
-                res_pawn.blue_pawn_list.remove(hex);
-                let tile = res_map.entities[hex];
-                if base_tile != tile{
-                    res_map.blue_entities.remove(&tile);
-                    commands.entity(tile).insert(
-                        res_map.default_mat.clone()
-                    );
-                }
-                commands.entity(pawn).insert(
-                    CombinationTarget{
-                        trans: query_transform.get(base_tile).unwrap().clone(),
-                        time: 0.
-                    }
+    for SimEvent(ev) in events_sim.iter(){
+        let GameEvent::Combined { team, base, category, consumed, piece } = ev else {continue};
+        let base_tile = res_map.entities[base];
+        for (_, hex) in consumed.iter(){
+            let occupant = res_map.vacate_kind(*hex, PieceKind::Pawn);
+            //기준 칸은 새 기물이 바로 올라오므로 팀 색을 유지함
+            if base != hex || piece.is_none(){
+                commands.entity(res_map.entities[hex]).insert(
+                    res_map.tile_mat(*hex)
                 );
             }
-            events_other.send(OtherSpawn { 
-                blue_team: true,
-                base_pos: base_hex, 
-                category: SpawnCategory::Rook
-            });
-        }
-        else if knight_patton(base_hex, hex_list.clone()){
-            let base_tile = res_map.entities[&base_hex];
-            for hex in hex_list.iter(){
-                let pawn = res_pawn.pawn_list.remove(hex).unwrap();
-                res_pawn.blue_pawn_list.remove(hex);
-                let tile = res_map.entities[hex];
-                if base_tile != tile{
-                    res_map.blue_entities.remove(&tile);
-                    commands.entity(tile).insert(
-                        res_map.default_mat.clone()
-                    );
+            let Some(pawn) = occupant else {continue};
+            commands.entity(pawn.entity).insert(
+                CombinationTarget{
+                    trans: query_transform.get(base_tile).unwrap().clone(),
+                    time: 0.
                 }
-                commands.entity(pawn).insert(
-                    CombinationTarget{
-                        trans: query_transform.get(base_tile).unwrap().clone(),
-                        time: 0.
-                    }
-                );
-            }
-            events_other.send(OtherSpawn { 
-                blue_team: true,
-                base_pos: base_hex, 
-                category: SpawnCategory::Knight
-            });
-        }
-    }
-}
-
-pub fn bishop_patton(
-    base_hex: Hex,
-    hex_list: HashSet<Hex>
-) -> bool{
-    if hex_list.len() != 3{
-        return false;
-    }
-    let patton = [
-        [Hex{x: base_hex.x + 1,y: base_hex.y}, Hex{x: base_hex.x + 2,y: base_hex.y}],
-        [Hex{x: base_hex.x + 1,y: base_hex.y}, Hex{x: base_hex.x + 1,y: base_hex.y + 1}],
-        [Hex{x: base_hex.x + 1,y: base_hex.y}, Hex{x: base_hex.x + 2,y: base_hex.y - 1}],
-
-        [Hex{x: base_hex.x - 1,y: base_hex.y}, Hex{x: base_hex.x - 2,y: base_hex.y}],
-        [Hex{x: base_hex.x - 1,y: base_hex.y}, Hex{x: base_hex.x - 2,y: base_hex.y + 1}],
-        [Hex{x: base_hex.x - 1,y: base_hex.y}, Hex{x: base_hex.x - 1,y: base_hex.y - 1}],
-
-        [Hex{x: base_hex.x,y: base_hex.y - 1}, Hex{x: base_hex.x,y: base_hex.y - 2}],
-        [Hex{x: base_hex.x,y: base_hex.y - 1}, Hex{x: base_hex.x - 1,y: base_hex.y - 1}],
-        [Hex{x: base_hex.x,y: base_hex.y - 1}, Hex{x: base_hex.x + 1,y: base_hex.y - 2}],
-
-        [Hex{x: base_hex.x,y: base_hex.y + 1}, Hex{x: base_hex.x,y: base_hex.y + 2}],
-        [Hex{x: base_hex.x,y: base_hex.y + 1}, Hex{x: base_hex.x + 1,y: base_hex.y + 1}],
-        [Hex{x: base_hex.x,y: base_hex.y + 1}, Hex{x: base_hex.x - 1,y: base_hex.y + 2}],
-
-        [Hex{x: base_hex.x + 1,y: base_hex.y - 1}, Hex{x: base_hex.x + 2,y: base_hex.y - 2}],
-        [Hex{x: base_hex.x + 1,y: base_hex.y - 1}, Hex{x: base_hex.x + 1,y: base_hex.y - 2}],
-        [Hex{x: base_hex.x + 1,y: base_hex.y - 1}, Hex{x: base_hex.x + 2,y: base_hex.y - 1}],
-
-        [Hex{x: base_hex.x - 1,y: base_hex.y + 1}, Hex{x: base_hex.x - 2,y: base_hex.y + 2}],
-        [Hex{x: base_hex.x - 1,y: base_hex.y + 1}, Hex{x: base_hex.x - 1,y: base_hex.y + 2}],
-        [Hex{x: base_hex.x - 1,y: base_hex.y + 1}, Hex{x: base_hex.x - 2,y: base_hex.y + 1}],
-    ];
-    let mut is_ok = true;
-    for p in patton.iter(){
-        is_ok = true;
-        for pp in p.iter(){
-            if !hex_list.contains(pp){
-                is_ok = false;
-                break;
-            }
-        }
-        if is_ok{
-            break;
-        }
-    }
-    return is_ok;
-}
-
-pub fn rook_patton(
-    base_hex: Hex,
-    hex_list: HashSet<Hex>
-) -> bool{
-    if hex_list.len() != 3{
-        return false;
-    }
-    let patton = [
-        [Hex{x: base_hex.x + 1,y: base_hex.y}, Hex{x: base_hex.x + 1,y: base_hex.y - 1}],
-        [Hex{x: base_hex.x + 1,y: base_hex.y}, Hex{x: base_hex.x,y: base_hex.y + 1}],
-
-        [Hex{x: base_hex.x,y: base_hex.y + 1}, Hex{x: base_hex.x + 1,y: base_hex.y}],
-        [Hex{x: base_hex.x,y: base_hex.y + 1}, Hex{x: base_hex.x - 1,y: base_hex.y + 1}],
-
-        [Hex{x: base_hex.x - 1,y: base_hex.y + 1}, Hex{x: base_hex.x,y: base_hex.y + 1}],
-        [Hex{x: base_hex.x - 1,y: base_hex.y + 1}, Hex{x: base_hex.x - 1,y: base_hex.y}],
-
-        [Hex{x: base_hex.x - 1,y: base_hex.y}, Hex{x: base_hex.x - 1,y: base_hex.y + 1}],
-        [Hex{x: base_hex.x - 1,y: base_hex.y}, Hex{x: base_hex.x,y: base_hex.y - 1}],
-        
-        [Hex{x: base_hex.x,y: base_hex.y - 1}, Hex{x: base_hex.x - 1,y: base_hex.y}],
-        [Hex{x: base_hex.x,y: base_hex.y - 1}, Hex{x: base_hex.x + 1,y: base_hex.y - 1}],
-
-        [Hex{x: base_hex.x + 1,y: base_hex.y - 1}, Hex{x: base_hex.x,y: base_hex.y - 1}],
-        [Hex{x: base_hex.x + 1,y: base_hex.y - 1}, Hex{x: base_hex.x + 1,y: base_hex.y}],
-    ];
-    let mut is_ok = true;
-    for p in patton.iter(){
-        is_ok = true;
-        for pp in p.iter(){
-            if !hex_list.contains(pp){
-                is_ok = false;
-                break;
-            }
-        }
-        if is_ok{
-            break;
+            );
         }
+        //이벤트 발생
+        events_other.send(OtherSpawn { 
+            id: *piece,
+            team: *team,
+            base_pos: *base, 
+            category: *category
+        });
     }
-    return is_ok;
 }
 
-pub fn knight_patton(
-    base_hex: Hex,
-    hex_list: HashSet<Hex>
-) -> bool{
-    if hex_list.len() != 4{
-        return false;
-    }
-    let patton = [
-        [Hex{x: base_hex.x + 1,y: base_hex.y}, Hex{x: base_hex.x + 2,y: base_hex.y}, Hex{x: base_hex.x + 3,y: base_hex.y - 1}],
-        [Hex{x: base_hex.x + 1,y: base_hex.y}, Hex{x: base_hex.x + 2,y: base_hex.y}, Hex{x: base_hex.x + 2,y: base_hex.y + 1}],
-
-        [Hex{x: base_hex.x - 1,y: base_hex.y}, Hex{x: base_hex.x - 2,y: base_hex.y}, Hex{x: base_hex.x - 2,y: base_hex.y - 1}],
-        [Hex{x: base_hex.x - 1,y: base_hex.y}, Hex{x: base_hex.x - 2,y: base_hex.y}, Hex{x: base_hex.x - 3,y: base_hex.y + 1}],
-
-        [Hex{x: base_hex.x,y: base_hex.y - 1}, Hex{x: base_hex.x,y: base_hex.y - 2}, Hex{x: base_hex.x + 1,y: base_hex.y - 3}],
-        [Hex{x: base_hex.x,y: base_hex.y - 1}, Hex{x: base_hex.x,y: base_hex.y - 2}, Hex{x: base_hex.x - 1,y: base_hex.y - 2}],
-
-        [Hex{x: base_hex.x,y: base_hex.y + 1}, Hex{x: base_hex.x,y: base_hex.y + 2}, Hex{x: base_hex.x + 1,y: base_hex.y + 2}],
-        [Hex{x: base_hex.x,y: base_hex.y + 1}, Hex{x: base_hex.x,y: base_hex.y + 2}, Hex{x: base_hex.x - 1,y: base_hex.y + 3}],
-
-        [Hex{x: base_hex.x + 1,y: base_hex.y - 1}, Hex{x: base_hex.x + 2,y: base_hex.y - 2}, Hex{x: base_hex.x + 2,y: base_hex.y - 3}],
-        [Hex{x: base_hex.x + 1,y: base_hex.y - 1}, Hex{x: base_hex.x + 2,y: base_hex.y - 2}, Hex{x: base_hex.x + 3,y: base_hex.y - 2}],
-
-        [Hex{x: base_hex.x - 1,y: base_hex.y + 1}, Hex{x: base_hex.x - 2,y: base_hex.y + 2}, Hex{x: base_hex.x - 2,y: base_hex.y + 3}],
-        [Hex{x: base_hex.x - 1,y: base_hex.y + 1}, Hex{x: base_hex.x - 2,y: base_hex.y + 2}, Hex{x: base_hex.x - 3,y: base_hex.y + 2}],
-    ];
-    let mut is_ok = true;
-    for p in patton.iter(){
-        is_ok = true;
-        for pp in p.iter(){
-            if !hex_list.contains(pp){
-                is_ok = false;
-                break;
-            }
-        }
-        if is_ok{
-            break;
-        }
+pub fn pawn_destroyed_event(
+    mut commands: Commands,
+    mut events_sim: EventReader<SimEvent>,
+    mut res_map: ResMut<Map>
+){
+    for SimEvent(ev) in events_sim.iter(){
+        let GameEvent::PieceDestroyed { kind: PieceKind::Pawn, team, pos, .. } = ev else {continue};
+        //같은 프레임에 다른 팀이 그 칸을 차지했다면 그대로 둠
+        if res_map.team_at(*pos) != Some(*team) {continue}
+        let Some(occupant) = res_map.vacate_kind(*pos, PieceKind::Pawn) else {continue};
+        commands.entity(res_map.entities[pos]).insert(res_map.tile_mat(*pos));
+        commands.entity(occupant.entity).despawn();
     }
-    return is_ok;
 }
@@ -0,0 +1,131 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use hexx::Hex;
+
+use crate::game::graphics_3d::honeycomb::{Map, Honeycomb};
+
+use super::{interaction::InteractionMode, game_sim::ActionEvent, simulation::{SpawnCategory, GameAction, Team}};
+
+const PICKER_BUTTON_WIDTH: f32 = 90.;
+const PICKER_BUTTON_HEIGHT: f32 = 36.;
+
+//여러 기물이 가능한 선택, 플레이어가 고를 때까지 폰은 그대로 있음
+#[derive(Event, Clone)]
+pub struct CombinationChoice{
+    pub team: Team,
+    pub base: Hex,
+    pub tiles: HashSet<Hex>,
+    pub choices: Vec<SpawnCategory>
+}
+
+#[derive(Resource, Default)]
+pub struct PickerRes{
+    pending: Option<CombinationChoice>,
+    menu: Option<Entity>
+}
+
+impl PickerRes{
+    pub fn close(&mut self, commands: &mut Commands){
+        self.pending = None;
+        if let Some(menu) = self.menu.take(){
+            commands.entity(menu).despawn_recursive();
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct PickerButton(SpawnCategory);
+
+fn category_name(category: SpawnCategory) -> &'static str{
+    match category {
+        SpawnCategory::Bishop => "Bishop",
+        SpawnCategory::Rook => "Rook",
+        SpawnCategory::Knight => "Knight",
+        SpawnCategory::Qean => "Queen"
+    }
+}
+
+//기준 칸 위치에 고를 수 있는 기물 버튼들을 띄움
+pub fn open_picker(
+    mut commands: Commands,
+    mut events_choice: EventReader<CombinationChoice>,
+    mut res_picker: ResMut<PickerRes>,
+    mut res_mode: ResMut<InteractionMode>,
+    res_map: Res<Map>,
+    query_camera: Query<(&Camera, &GlobalTransform)>,
+    query_transform: Query<&GlobalTransform, With<Honeycomb>>
+){
+    for ev in events_choice.iter(){
+        if let Some(menu) = res_picker.menu.take(){
+            commands.entity(menu).despawn_recursive();
+        }
+        let Ok((camera, camera_trans)) = query_camera.get_single() else {continue};
+        let tile_trans = query_transform.get(res_map.entities[&ev.base]).unwrap().translation();
+        let Some(screen_pos) = camera.world_to_viewport(camera_trans, tile_trans) else {continue};
+
+        let menu = commands.spawn(NodeBundle{
+            style: Style{
+                position_type: PositionType::Absolute,
+                left: Val::Px(screen_pos.x - PICKER_BUTTON_WIDTH / 2.),
+                top: Val::Px(screen_pos.y - PICKER_BUTTON_HEIGHT * ev.choices.len() as f32 / 2.),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.),
+                ..default()
+            },
+            ..default()
+        }).with_children(|p|{
+            for category in ev.choices.iter(){
+                p.spawn((
+                    ButtonBundle{
+                        style: Style{
+                            width: Val::Px(PICKER_BUTTON_WIDTH),
+                            height: Val::Px(PICKER_BUTTON_HEIGHT),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: Color::rgba(0.1, 0.1, 0.1, 0.85).into(),
+                        ..default()
+                    },
+                    PickerButton(*category)
+                )).with_children(|p|{
+                    p.spawn(TextBundle::from_section(
+                        category_name(*category),
+                        TextStyle{
+                            font_size: 20.,
+                            color: Color::WHITE,
+                            ..default()
+                        }
+                    ));
+                });
+            }
+        }).id();
+        res_picker.menu = Some(menu);
+        res_picker.pending = Some(ev.clone());
+        *res_mode = InteractionMode::Menu;
+    }
+}
+
+pub fn picker_button_click(
+    mut commands: Commands,
+    query_button: Query<(&Interaction, &PickerButton), Changed<Interaction>>,
+    mut res_picker: ResMut<PickerRes>,
+    mut res_mode: ResMut<InteractionMode>,
+    mut events_action: EventWriter<ActionEvent>
+){
+    for (interaction, button) in query_button.iter(){
+        if *interaction != Interaction::Pressed{
+            continue;
+        }
+        let Some(pending) = res_picker.pending.take() else {continue};
+        events_action.send(ActionEvent(GameAction::Combine {
+            team: pending.team,
+            base: pending.base,
+            tiles: pending.tiles,
+            category: Some(button.0)
+        }));
+        res_picker.close(&mut commands);
+        *res_mode = InteractionMode::Idle;
+    }
+}
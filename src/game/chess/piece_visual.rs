@@ -0,0 +1,199 @@
+use std::marker::PhantomData;
+
+use bevy::{prelude::*, ecs::system::EntityCommands};
+use bevy_mod_picking::prelude::{On, Click, Pointer};
+
+use crate::game::graphics_3d::honeycomb::{Map, Occupant};
+
+use super::{pawn::{SpawnAnimToggle, OtherSpawn}, rules::RulesRes, game_sim::{GameSim, SimEvent}, simulation::{PieceId, PieceKind, GameEvent}};
+
+//폰을 뺀 기물들이 같이 쓰는 모델, 등장/대기 애니메이션, 파괴 처리
+//각 기물 파일에는 공격에 필요한 것만 남김
+pub trait PieceVisual: Component + Sized{
+    const KIND: PieceKind;
+    const NAME: &'static str; //모델 파일(<NAME>.glb)과 애니메이션 이름에 씀
+    const SCALE: f32 = 0.4; //MODEL_HEX_SIZE 칸에서의 모델 크기
+
+    fn new(id: PieceId) -> Self;
+
+    //대기 중에 반복하는 애니메이션
+    fn idle_curve() -> VariableCurve;
+
+    //기물이 올라온 칸에 붙일 것, 조종할 수 있는 기물은 클릭 리스너를 붙임
+    fn on_spawn(_tile: &mut EntityCommands){}
+}
+
+#[derive(Resource)]
+pub struct PieceRes<P: PieceVisual>{
+    pub mesh: Handle<Mesh>,
+    spawn_animation: (Handle<AnimationClip>, Name),
+    pub idle_animation: (Handle<AnimationClip>, Name),
+    marker: PhantomData<P>
+}
+
+//땅속에서 튀어올라 칸 위에 자리잡음, 모든 기물이 같은 움직임
+pub fn spawn_curve(hex_size: f32) -> VariableCurve{
+    VariableCurve {
+        keyframe_timestamps: vec![0.,0.6,1.2],
+        keyframes: Keyframes::Translation(
+            vec![
+                Vec3::new(0., hex_size * -2., 0.),
+                Vec3::new(0., hex_size * 1.2, 0.),
+                Vec3::new(0., hex_size / 3., 0.)
+            ]
+        )
+    }
+}
+
+pub fn setup_asset_piece<P: PieceVisual>(
+    mut commands: Commands,
+    assets_server: Res<AssetServer>,
+    mut animations: ResMut<Assets<AnimationClip>>,
+    res_rules: Res<RulesRes>
+){
+    let spawn_anim = Name::new(format!("{}_spawn", P::NAME));
+    let mut spawn_animation = AnimationClip::default();
+    spawn_animation.add_curve_to_path(EntityPath{parts: vec![spawn_anim.clone()]}, spawn_curve(res_rules.hex_size));
+    let idle_anim = Name::new(format!("{}_idle", P::NAME));
+    let mut idle_animation = AnimationClip::default();
+    idle_animation.add_curve_to_path(EntityPath{parts: vec![idle_anim.clone()]}, P::idle_curve());
+
+    let spawn_animation_handle = animations.add(spawn_animation);
+    let idle_animation_handle = animations.add(idle_animation);
+    commands.insert_resource(
+        PieceRes::<P>{
+            mesh: assets_server.load(format!("{}.glb#Mesh0/Primitive0", P::NAME)),
+            spawn_animation: (spawn_animation_handle, spawn_anim),
+            idle_animation: (idle_animation_handle, idle_anim),
+            marker: PhantomData
+        }
+    );
+}
+
+//조합으로 만든 기물(OtherSpawn)과 맵에 처음부터 놓인 기물(PieceSpawned) 모두 여기서 모델을 만듦
+pub fn piece_spawn_event<P: PieceVisual>(
+    mut commands: Commands,
+    mut res_map: ResMut<Map>,
+    res_piece: Res<PieceRes<P>>,
+    res_sim: Res<GameSim>,
+    res_rules: Res<RulesRes>,
+    mut events_sim: EventReader<SimEvent>,
+    mut events_other: EventReader<OtherSpawn>
+){
+    let placed = events_sim.iter().filter_map(|SimEvent(ev)| match ev {
+        GameEvent::PieceSpawned { id, kind, team, pos, .. } if *kind == P::KIND => Some((*id, *team, *pos)),
+        _ => None
+    });
+    let combined = events_other.iter()
+        .filter(|ev| ev.category.kind() == P::KIND)
+        .filter_map(|ev| Some((ev.id?, ev.team, ev.base_pos)));
+    for (id, team, pos) in placed.chain(combined){
+        //같은 프레임에 이미 사라진 기물은 만들지 않음
+        if res_sim.state.piece(id).is_none() {continue}
+        let mat = res_map.team_color(team);
+        let mut player = AnimationPlayer::default();
+        player.play(res_piece.spawn_animation.0.clone());
+        let mut trans = Transform::from_xyz(0., res_rules.hex_size/3. , 0.);
+        trans.scale = Vec3::splat(P::SCALE * res_rules.model_scale());
+
+        let mut tile = commands.entity(res_map.entities[&pos]);
+        if res_map.aim_at(pos).is_none(){
+            tile.insert(mat.clone());
+        }
+        let mut entity = Entity::from_bits(0);
+        tile.with_children(|p|{
+            entity = p.spawn(
+                (
+                    PbrBundle{
+                        mesh: res_piece.mesh.clone(),
+                        material: mat,
+                        transform: trans,
+                        ..Default::default()
+                    },
+                    P::new(id),
+                    res_piece.spawn_animation.1.clone(),
+                    player,
+                    SpawnAnimToggle
+                )
+            ).id();
+        });
+        P::on_spawn(&mut tile);
+        res_map.occupy(pos, Occupant { team, kind: P::KIND, entity });
+    }
+}
+
+pub fn piece_spawn_anim_is_end<P: PieceVisual>(
+    mut commands: Commands,
+    mut query_player: Query<(&mut AnimationPlayer, Entity), (With<SpawnAnimToggle>, With<P>)>,
+    res_piece: Res<PieceRes<P>>,
+){
+    for (mut player, ent) in query_player.iter_mut(){
+        if player.elapsed() > 1.2{
+            let mut entity = commands.entity(ent);
+            entity.remove::<SpawnAnimToggle>();
+            player.play(res_piece.idle_animation.0.clone()).repeat();
+            entity.insert(res_piece.idle_animation.1.clone());
+        }
+    }
+}
+
+pub fn piece_destroyed_event<P: PieceVisual>(
+    mut commands: Commands,
+    mut events_sim: EventReader<SimEvent>,
+    mut res_map: ResMut<Map>
+){
+    for SimEvent(ev) in events_sim.iter(){
+        let GameEvent::PieceDestroyed { kind, team, pos, .. } = ev else {continue};
+        if *kind != P::KIND || res_map.team_at(*pos) != Some(*team) {continue}
+        let Some(occupant) = res_map.vacate_kind(*pos, P::KIND) else {continue};
+        let tile = res_map.entities[pos];
+        commands.entity(tile).remove::<On::<Pointer<Click>>>();
+        commands.entity(tile).insert(res_map.tile_mat(*pos));
+        commands.entity(occupant.entity).despawn_recursive();
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use hexx::Hex;
+
+    use super::*;
+    use super::super::{rook::Rook, pawn::place_start_pieces, rules::RulesRes, simulation::{BoardShape, GameRules, MapFile, PieceDef, Team}};
+
+    //맵 파일에 있던 기물도 조합한 기물처럼 모델이 생기고 점유 칸에 들어가야 함
+    #[test]
+    fn map_pieces_occupy_their_tile(){
+        let mut app = App::new();
+        let mut map = Map { team_mats: vec![Handle::default(); 2], ..Default::default() };
+        for hex in Hex::ZERO.range(3){
+            let tile = app.world.spawn_empty().id();
+            map.entities.insert(hex, tile);
+        }
+        let mut sim = GameSim::new(&map, Some(1), &GameRules::default());
+        let mut board = MapFile::from_shape(&BoardShape::Hexagon { radius: 3 }, &[]);
+        board.pieces.push(PieceDef { team: Team::RED, kind: PieceKind::Rook, x: 1, y: -1 });
+        place_start_pieces(&mut sim.state, &board);
+        let events = sim.state.drain_events();
+
+        app.insert_resource(map)
+            .insert_resource(sim)
+            .insert_resource(RulesRes(GameRules::default()))
+            .insert_resource(PieceRes::<Rook>{
+                mesh: Handle::default(),
+                spawn_animation: (Handle::default(), Name::new("rook_spawn")),
+                idle_animation: (Handle::default(), Name::new("rook_idle")),
+                marker: PhantomData
+            })
+            .add_event::<SimEvent>()
+            .add_event::<OtherSpawn>()
+            .add_systems(Update, piece_spawn_event::<Rook>);
+        for ev in events{
+            app.world.send_event(SimEvent(ev));
+        }
+        app.update();
+
+        let occupant = *app.world.resource::<Map>().occupant_of(Hex::new(1, -1), PieceKind::Rook).unwrap();
+        assert_eq!(occupant.team, Team::RED);
+        assert!(app.world.get::<Rook>(occupant.entity).is_some());
+    }
+}
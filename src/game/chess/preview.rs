@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use hexx::Hex;
+
+use crate::game::graphics_3d::honeycomb::Map;
+
+use super::{bishop::Bishop, rook::Rook, knight::Knight, queen::Queen, piece_visual::PieceVisual, rules::RulesRes, interaction::InteractionMode, game_sim::{GameSim, PlayerControl}, simulation::SpawnCategory};
+
+#[derive(Resource)]
+pub struct PreviewRes{
+    models: HashMap<SpawnCategory, (Handle<Mesh>, f32)>, //기물 모델과 그 기물의 SCALE
+    ghost_mat: Handle<StandardMaterial>,
+    ghost: Option<Entity>,
+    last_selection: Option<(usize, Hex)>, //마지막으로 확인한 (선택 수, 기준 칸)
+    last_result: Option<SpawnCategory>
+}
+
+//드래그 중에 만들어질 기물을 미리 보여주는 반투명 모델
+#[derive(Component)]
+pub struct PreviewGhost;
+
+pub fn setup_preview(
+    mut commands: Commands,
+    assets_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<StandardMaterial>>
+){
+    let models = [
+        (SpawnCategory::Bishop, Bishop::NAME, Bishop::SCALE),
+        (SpawnCategory::Rook, Rook::NAME, Rook::SCALE),
+        (SpawnCategory::Knight, Knight::NAME, Knight::SCALE),
+        (SpawnCategory::Qean, Queen::NAME, Queen::SCALE),
+    ].iter()
+        .map(|(category, name, scale)| (*category, (assets_server.load(format!("{}.glb#Mesh0/Primitive0", name)), *scale)))
+        .collect();
+    commands.insert_resource(PreviewRes{
+        models,
+        ghost_mat: materials.add(StandardMaterial{
+            base_color: Color::rgba(1., 1., 1., 0.45),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        }),
+        ghost: None,
+        last_selection: None,
+        last_result: None
+    });
+}
+
+//드래그 선택 중에는 매 프레임 선택된 칸을 조합 규칙과 비교함
+pub fn combination_preview(
+    mut commands: Commands,
+    mut res_preview: ResMut<PreviewRes>,
+    mut res_map: ResMut<Map>,
+    res_sim: Res<GameSim>,
+    res_control: Res<PlayerControl>,
+    res_mode: Res<InteractionMode>,
+    res_rules: Res<RulesRes>
+){
+    if *res_mode != InteractionMode::DragSelecting || res_map.selected_list.is_empty(){
+        //선택이 끝나면 타일 색은 selected_mod 에서 되돌림
+        if let Some(ghost) = res_preview.ghost.take(){
+            commands.entity(ghost).despawn_recursive();
+        }
+        res_preview.last_selection = None;
+        res_preview.last_result = None;
+        return;
+    }
+    let selection = (res_map.selected_list.len(), res_map.selected_base);
+    if res_preview.last_selection == Some(selection){
+        return;
+    }
+    //여러 기물이 가능하면 첫 번째 기물을 보여주고 놓을 때 고르게 함
+    let result = res_sim.state.preview_combinations(
+        res_control.team,
+        res_map.selected_base,
+        &res_map.selected_list
+    ).first().copied();
+    res_map.seleced_invalid = result.is_none();
+    let mat = res_map.selected_mat();
+    for hex in res_map.selected_list.iter(){
+        commands.entity(res_map.entities[hex]).insert(mat.clone());
+    }
+
+    let base_changed = res_preview.last_selection.map_or(true, |(_, base)| base != selection.1);
+    if base_changed || res_preview.last_result != result{
+        if let Some(ghost) = res_preview.ghost.take(){
+            commands.entity(ghost).despawn_recursive();
+        }
+        if let Some(category) = result{
+            let (mesh, scale) = res_preview.models[&category].clone();
+            let mut trans = Transform::from_xyz(0., res_rules.hex_size/3. , 0.);
+            trans.scale = Vec3::splat(scale * res_rules.model_scale());
+            let mut ghost = Entity::from_bits(0);
+            commands.entity(res_map.entities[&selection.1]).with_children(|p|{
+                ghost = p.spawn((
+                    PbrBundle{
+                        mesh,
+                        material: res_preview.ghost_mat.clone(),
+                        transform: trans,
+                        ..Default::default()
+                    },
+                    PreviewGhost
+                )).id();
+            });
+            res_preview.ghost = Some(ghost);
+        }
+    }
+    res_preview.last_selection = Some(selection);
+    res_preview.last_result = result;
+}
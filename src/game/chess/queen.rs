@@ -0,0 +1,159 @@
+use std::f32::consts::PI;
+
+use bevy::{prelude::*, ecs::system::EntityCommands};
+use bevy_mod_picking::prelude::{On, Click, Pointer, Listener, PointerButton};
+
+use crate::game::graphics_3d::honeycomb::{Map, Honeycomb, AimTarget};
+
+use super::{piece_visual::PieceVisual, rules::RulesRes, interaction::{InteractionMode, AimEvent, begin_aim}, game_sim::{GameSim, SimEvent, PlayerControl, ActionEvent}, simulation::{PieceId, PieceKind, GameEvent, GameAction, HexBoard, RayStep}};
+
+//퀸만 쓰는 관통탄 모델, 퀸 모델과 애니메이션은 PieceRes<Queen>
+#[derive(Resource)]
+pub struct QueenRes{
+    beam_mesh: Handle<Mesh>
+}
+
+#[derive(Component)]
+pub struct Queen;
+
+//관통탄의 머리 부분, 위치는 시뮬레이션의 칸과 진행도로 계산함
+#[derive(Component)]
+pub struct BeamShell{
+    id: PieceId
+}
+
+//퀸은 제자리에서 천천히 회전함
+impl PieceVisual for Queen{
+    const KIND: PieceKind = PieceKind::Queen;
+    const NAME: &'static str = "queen";
+
+    fn new(_: PieceId) -> Self{
+        Queen
+    }
+
+    fn idle_curve() -> VariableCurve{
+        VariableCurve {
+            keyframe_timestamps: vec![0.,2.,4.,6.],
+            keyframes: Keyframes::Rotation(
+                vec![
+                    Quat::IDENTITY,
+                    Quat::from_rotation_y(PI * 2. / 3.),
+                    Quat::from_rotation_y(PI * 4. / 3.),
+                    Quat::IDENTITY
+                ]
+            )
+        }
+    }
+
+    fn on_spawn(tile: &mut EntityCommands){
+        tile.insert(On::<Pointer<Click>>::run(on_queen_click));
+    }
+}
+
+pub fn setup_asset_queen(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    res_rules: Res<RulesRes>
+){
+    commands.insert_resource(
+        QueenRes{
+            beam_mesh: meshes.add(Mesh::from(shape::Capsule{
+                radius: res_rules.hex_size * 0.15,
+                depth: res_rules.hex_size * 1.5,
+                ..default()
+            }))
+        }
+    );
+}
+
+fn on_queen_click(
+    mut commands: Commands,
+    event: Listener<Pointer<Click>>,
+    mut res_map: ResMut<Map>,
+    mut res_mode: ResMut<InteractionMode>,
+    res_control: Res<PlayerControl>
+){
+    if event.button == PointerButton::Primary{
+        let base_tile = res_map.entities_forentity[&event.target];
+        let Some(queen) = res_map.occupant_of(base_tile, PieceKind::Queen) else {return};
+        let team = queen.team;
+        if team != res_control.team {return}
+        if !begin_aim(&mut commands, &mut res_mode, &mut res_map, base_tile) {return}
+        //관통탄이라 적이 있어도 보드 끝까지 경로를 표시함
+        res_map.offer_directions(&mut commands, base_tile, |map, hex|{
+            if map.is_blocked(hex){
+                RayStep::Stop
+            }
+            else if map.team_at(hex) == Some(team){
+                RayStep::Skip
+            }
+            else{
+                RayStep::Mark
+            }
+        });
+    }
+}
+
+pub fn queen_aim_event(
+    mut events_aim: EventReader<AimEvent>,
+    mut events_action: EventWriter<ActionEvent>,
+    res_map: Res<Map>,
+    res_control: Res<PlayerControl>
+){
+    for ev in events_aim.iter(){
+        let AimTarget::Direction(direction) = ev.target else {continue};
+        if res_map.occupant_of(ev.piece, PieceKind::Queen).is_none() {continue}
+        events_action.send(ActionEvent(GameAction::Fire { team: res_control.team, from: ev.piece, direction }));
+    }
+}
+
+pub fn queen_fire_event(
+    mut commands: Commands,
+    mut events_sim: EventReader<SimEvent>,
+    res_queen: Res<QueenRes>,
+    res_map: Res<Map>,
+    res_rules: Res<RulesRes>,
+    query_transform: Query<&GlobalTransform, With<Honeycomb>>
+){
+    for SimEvent(ev) in events_sim.iter(){
+        let GameEvent::Fired { id, from } = ev else {continue};
+        let from_trans = query_transform.get(res_map.entities[from]).unwrap().translation();
+        let mat = res_map.team_mat(*from);
+        commands.spawn((
+            PbrBundle{
+                mesh: res_queen.beam_mesh.clone(),
+                material: mat,
+                transform: Transform::from_translation(from_trans + Vec3::Y * res_rules.hex_size),
+                ..Default::default()
+            },
+            BeamShell{
+                id: *id
+            }
+        ));
+    }
+}
+
+pub fn beam_flying(
+    mut commands: Commands,
+    mut query_beam: Query<(Entity, &BeamShell, &mut Transform)>,
+    query_transform: Query<&GlobalTransform, With<Honeycomb>>,
+    res_sim: Res<GameSim>,
+    res_map: Res<Map>,
+    res_rules: Res<RulesRes>
+){
+    for (entity, shell, mut trans) in query_beam.iter_mut(){
+        //시뮬레이션에서 사라졌다면 보드 끝에 닿았거나 막힌것
+        let Some(beam) = res_sim.state.beam(shell.id) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        let target_pos = beam.pos + beam.direction;
+        let Some(target_entity) = res_map.entities.get(&target_pos) else {continue};
+        let base_trans = query_transform.get(res_map.entities[&beam.pos]).unwrap().translation();
+        let target_trans = query_transform.get(*target_entity).unwrap().translation();
+        trans.translation = base_trans.lerp(target_trans, beam.progress) + Vec3::Y * res_rules.hex_size;
+        //캡슐이 진행 방향으로 눕도록 회전
+        trans.look_at(target_trans + Vec3::Y * res_rules.hex_size, Vec3::Y);
+        trans.rotate_local_x(PI / 2.);
+    }
+}
@@ -0,0 +1,71 @@
+use bevy::{prelude::*, asset::{AssetLoader, LoadContext, LoadedAsset}, reflect::{TypeUuid, TypePath}, utils::BoxedFuture};
+use serde::Deserialize;
+
+use super::{game_sim::GameSim, replay::MatchRecord, simulation::Recipe};
+
+pub const RECIPE_PATH: &str = "combination.recipes.json";
+
+//조합 규칙 목록 에셋
+#[derive(Debug, Deserialize, TypeUuid, TypePath)]
+#[uuid = "6b1f3c52-93d4-4b0e-a7b2-2c8e5f1d9a41"]
+pub struct RecipeBook(pub Vec<Recipe>);
+
+#[derive(Default)]
+pub struct RecipeLoader;
+
+impl AssetLoader for RecipeLoader{
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let book: RecipeBook = serde_json::from_slice(bytes)?;
+            for recipe in book.0.iter(){
+                recipe.validate()?;
+            }
+            load_context.set_default_asset(LoadedAsset::new(book));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["recipes.json"]
+    }
+}
+
+#[derive(Resource)]
+pub struct RecipeHandle(pub Handle<RecipeBook>);
+
+pub fn setup_recipe(
+    mut commands: Commands,
+    assets_server: Res<AssetServer>
+){
+    commands.insert_resource(RecipeHandle(assets_server.load(RECIPE_PATH)));
+}
+
+//로드되거나 파일이 수정되면 시뮬레이션의 조합 규칙을 교체함
+//기록 중인 판은 첫 행동 전까지만 바꾸고 기록에도 남김, 그 뒤의 수정은 리플레이가 어긋나지 않도록 무시함
+pub fn recipe_loaded_event(
+    mut events_asset: EventReader<AssetEvent<RecipeBook>>,
+    res_books: Res<Assets<RecipeBook>>,
+    res_handle: Res<RecipeHandle>,
+    mut res_sim: ResMut<GameSim>,
+    mut res_record: Option<ResMut<MatchRecord>>
+){
+    for ev in events_asset.iter(){
+        let (AssetEvent::Created { handle } | AssetEvent::Modified { handle }) = ev else {continue};
+        if *handle != res_handle.0{
+            continue;
+        }
+        let Some(book) = res_books.get(handle) else {continue};
+        if let Some(record) = res_record.as_mut(){
+            if !record.0.actions.is_empty(){
+                warn!("{} changed during a recorded match, keeping the recipes the match started with", RECIPE_PATH);
+                continue;
+            }
+            record.0.recipes = book.0.clone();
+        }
+        res_sim.state.set_recipes(book.0.clone());
+    }
+}
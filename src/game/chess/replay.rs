@@ -0,0 +1,236 @@
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::{On, Click, Pointer};
+use hexx::Hex;
+
+use crate::game::graphics_3d::honeycomb::Map;
+
+use super::{game_sim::{GameSim, SimEvent, ActionEvent}, pawn::place_start_pieces, map_asset::BoardRes, rules::RulesRes, outcome::{GameOutcome, ResultScreen}, bishop::BishopAttack, knight::GrenadeShell, queen::BeamShell, simulation::{GameEvent, Replay}};
+
+pub const REPLAY_DIR: &str = "replays";
+const SEEK_SECONDS: f32 = 5.;
+const SPEED_MIN: f32 = 0.25;
+const SPEED_MAX: f32 = 8.;
+
+//지금 하는 판의 기록, 리플레이를 보는 중에는 없음
+#[derive(Resource)]
+pub struct MatchRecord(pub Replay);
+
+//리플레이를 보는 중일 때만 있는 리소스, 앱을 만들 때 미리 넣어두면 실행 인자 대신 그 리플레이를 봄
+#[derive(Resource)]
+pub struct Playback{
+    pub replay: Replay,
+    cursor: usize, //다음에 보낼 행동
+    target: f32, //지금 보여줘야 하는 스텝
+    speed: f32,
+    paused: bool
+}
+
+impl Playback{
+    pub fn new(replay: Replay) -> Self{
+        Self { replay, cursor: 0, target: 0., speed: 1., paused: false }
+    }
+}
+
+#[derive(Component)]
+pub struct ReplayText;
+
+//실행 인자에 --replay <파일>이 있으면 그 파일 경로
+pub fn replay_arg() -> Option<String>{
+    let mut args = std::env::args().skip_while(|arg| arg != "--replay");
+    args.next()?;
+    args.next()
+}
+
+pub fn load_replay(path: &str) -> Result<Replay, Box<dyn std::error::Error>>{
+    let replay: Replay = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    replay.validate()?;
+    Ok(replay)
+}
+
+fn write_replay(replay: &Replay){
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let path = format!("{}/{}.replay.json", REPLAY_DIR, replay.seed);
+        let result = serde_json::to_string(replay)
+            .map_err(std::io::Error::from)
+            .and_then(|text| std::fs::create_dir_all(REPLAY_DIR).and_then(|_| std::fs::write(&path, text)));
+        match result {
+            Ok(()) => info!("replay saved to {}", path),
+            Err(err) => warn!("could not save replay {}: {}", path, err)
+        }
+    }
+}
+
+fn clock(seconds: f32) -> String{
+    let seconds = seconds as u32;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+//기물을 놓은 뒤부터 적용된 행동을 기록함, 판을 다시 만들 수 있도록 지금의 규칙과 보드, 조합 규칙을 같이 남김
+pub fn setup_recording(
+    mut commands: Commands,
+    res_sim: Res<GameSim>,
+    res_rules: Res<RulesRes>,
+    res_board: Res<BoardRes>,
+    res_time: Res<FixedTime>
+){
+    commands.insert_resource(MatchRecord(Replay::new(
+        res_sim.state.seed(),
+        res_rules.0.clone(),
+        res_board.0.clone(),
+        res_sim.state.recipes().to_vec(),
+        res_time.period.as_secs_f32()
+    )));
+}
+
+//리플레이는 기록된 조합 규칙으로 진행함, 규칙과 보드는 플러그인을 만들 때 이미 넣어둠
+pub fn setup_playback(
+    res_playback: Res<Playback>,
+    mut res_sim: ResMut<GameSim>
+){
+    res_sim.state.set_recipes(res_playback.replay.recipes.clone());
+}
+
+//판이 끝나거나 F5를 누르면 지금까지의 기록을 파일로 남김
+pub fn save_replay(
+    mut events_sim: EventReader<SimEvent>,
+    res_key: Res<Input<KeyCode>>,
+    res_sim: Res<GameSim>,
+    mut res_record: ResMut<MatchRecord>
+){
+    let game_over = events_sim.iter().any(|SimEvent(ev)| matches!(ev, GameEvent::GameOver { .. }));
+    if game_over || res_key.just_pressed(KeyCode::F5){
+        res_record.0.length = res_sim.ticks;
+        write_replay(&res_record.0);
+    }
+}
+
+pub fn setup_replay_hud(
+    mut commands: Commands
+){
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle{
+                font_size: 18.,
+                color: Color::WHITE,
+                ..default()
+            }
+        ).with_style(Style{
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(8.),
+            left: Val::Px(8.),
+            ..default()
+        }),
+        ReplayText
+    ));
+}
+
+//Space 멈춤, 위아래 속도, 좌우 앞뒤로 이동
+pub fn replay_controls(
+    res_key: Res<Input<KeyCode>>,
+    mut res_playback: ResMut<Playback>
+){
+    let seek = SEEK_SECONDS / res_playback.replay.timestep;
+    if res_key.just_pressed(KeyCode::Space){
+        res_playback.paused = !res_playback.paused;
+    }
+    if res_key.just_pressed(KeyCode::Up){
+        res_playback.speed = (res_playback.speed * 2.).min(SPEED_MAX);
+    }
+    if res_key.just_pressed(KeyCode::Down){
+        res_playback.speed = (res_playback.speed / 2.).max(SPEED_MIN);
+    }
+    if res_key.just_pressed(KeyCode::Right){
+        res_playback.target += seek;
+    }
+    if res_key.just_pressed(KeyCode::Left){
+        res_playback.target = (res_playback.target - seek).max(0.);
+    }
+}
+
+//되감을 때는 보드를 처음 상태로 되돌리고 replay_playback이 다시 진행함
+pub fn replay_rewind(
+    mut commands: Commands,
+    mut res_playback: ResMut<Playback>,
+    mut res_sim: ResMut<GameSim>,
+    mut res_map: ResMut<Map>,
+    res_board: Res<BoardRes>,
+    res_rules: Res<RulesRes>,
+    mut events_sim: EventWriter<SimEvent>,
+    query_flying: Query<Entity, Or<(With<BishopAttack>, With<GrenadeShell>, With<BeamShell>)>>,
+    query_screen: Query<Entity, With<ResultScreen>>,
+    state: Res<State<GameOutcome>>,
+    mut next_state: ResMut<NextState<GameOutcome>>
+){
+    if res_playback.target as u64 >= res_sim.ticks{
+        return;
+    }
+    let mut sim = GameSim::new(&res_map, Some(res_playback.replay.seed), &res_rules);
+    sim.state.set_recipes(res_playback.replay.recipes.clone());
+    place_start_pieces(&mut sim.state, &res_board);
+    for ev in sim.state.drain_events(){
+        events_sim.send(SimEvent(ev));
+    }
+    *res_sim = sim;
+    res_playback.cursor = 0;
+
+    let occupied: Vec<Hex> = res_map.occupants.keys().copied().collect();
+    for hex in occupied{
+        let Some(occupant) = res_map.vacate(hex) else {continue};
+        commands.entity(occupant.entity).despawn_recursive();
+        let tile = res_map.entities[&hex];
+        commands.entity(tile).remove::<On::<Pointer<Click>>>();
+        commands.entity(tile).insert(res_map.tile_mat(hex));
+    }
+    for entity in query_flying.iter().chain(query_screen.iter()){
+        commands.entity(entity).despawn_recursive();
+    }
+    if *state.get() != GameOutcome::Playing{
+        next_state.set(GameOutcome::Playing);
+    }
+}
+
+//기록된 스텝에 맞춰 행동을 보내고 시뮬레이션을 진행함, 실제 판과 같은 ActionEvent로 적용됨
+pub fn replay_playback(
+    res_time: Res<Time>,
+    mut res_playback: ResMut<Playback>,
+    mut res_sim: ResMut<GameSim>,
+    mut events_sim: EventWriter<SimEvent>,
+    mut events_action: EventWriter<ActionEvent>
+){
+    let playback = &mut *res_playback;
+    if !playback.paused{
+        playback.target += res_time.delta_seconds() * playback.speed / playback.replay.timestep;
+    }
+    playback.target = playback.target.min(playback.replay.length as f32);
+
+    while res_sim.ticks < playback.target as u64{
+        //이번 스텝 전에 적용할 행동이 있으면 보내고, 이 프레임에 적용된 뒤 다음 프레임에 이어서 진행함
+        let due = playback.replay.due(playback.cursor, res_sim.ticks);
+        if !due.is_empty(){
+            for timed in due.iter(){
+                events_action.send(ActionEvent(timed.action.clone()));
+            }
+            playback.cursor += due.len();
+            return;
+        }
+        res_sim.step(playback.replay.timestep, &mut events_sim);
+    }
+}
+
+pub fn replay_hud_update(
+    res_playback: Res<Playback>,
+    res_sim: Res<GameSim>,
+    mut query_text: Query<&mut Text, With<ReplayText>>
+){
+    let Ok(mut text) = query_text.get_single_mut() else {return};
+    let replay = &res_playback.replay;
+    text.sections[0].value = format!(
+        "REPLAY {}x  {} / {}{}\nSpace: pause  Up/Down: speed  Left/Right: seek",
+        res_playback.speed,
+        clock(replay.seconds(res_sim.ticks)),
+        clock(replay.seconds(replay.length)),
+        if res_playback.paused {"  PAUSED"} else {""}
+    );
+}
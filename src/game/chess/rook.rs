@@ -0,0 +1,109 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+use crate::game::graphics_3d::honeycomb::Map;
+
+use super::{piece_visual::{PieceVisual, PieceRes}, rules::RulesRes, game_sim::{GameSim, SimEvent}, simulation::{PieceId, PieceKind, GameEvent}};
+
+//록만 쓰는 막기 애니메이션, 모델과 나머지 애니메이션은 PieceRes<Rook>
+#[derive(Resource)]
+pub struct RookRes{
+    block_animation: (Handle<AnimationClip>, Name)
+}
+
+#[derive(Component)]
+pub struct Rook;
+
+#[derive(Component)]
+pub struct BlockAnimToggle;
+
+//록은 무겁게 천천히 좌우로만 흔들림
+impl PieceVisual for Rook{
+    const KIND: PieceKind = PieceKind::Rook;
+    const NAME: &'static str = "rook";
+
+    fn new(_: PieceId) -> Self{
+        Rook
+    }
+
+    fn idle_curve() -> VariableCurve{
+        VariableCurve {
+            keyframe_timestamps: vec![0.,3.,6.],
+            keyframes: Keyframes::Rotation(
+                vec![
+                    Quat::from_euler(EulerRot::XYZ, 0., PI/12. * -1., 0.),
+                    Quat::from_euler(EulerRot::XYZ, 0., PI/12., 0.),
+                    Quat::from_euler(EulerRot::XYZ, 0., PI/12. * -1., 0.)
+                ]
+            )
+        }
+    }
+}
+
+pub fn setup_asset_rook(
+    mut commands: Commands,
+    mut animations: ResMut<Assets<AnimationClip>>,
+    res_rules: Res<RulesRes>
+){
+    let scale = Rook::SCALE * res_rules.model_scale();
+    //로켓을 막았을 때 방패가 크게 부풀었다가 돌아옴
+    let block_anim = Name::new("rook_block");
+    let mut block_animation = AnimationClip::default();
+    block_animation.add_curve_to_path(
+        EntityPath { parts: vec![block_anim.clone()] },
+        VariableCurve {
+            keyframe_timestamps: vec![0.,0.2,0.8],
+            keyframes: Keyframes::Scale(
+                vec![
+                    Vec3::splat(scale),
+                    Vec3::splat(scale * 1.5),
+                    Vec3::splat(scale)
+                ]
+            )
+        }
+    );
+
+    let block_animation_handle = animations.add(block_animation);
+    commands.insert_resource(
+        RookRes{
+            block_animation:(block_animation_handle, block_anim)
+        }
+    );
+}
+
+pub fn rook_block_event(
+    mut commands: Commands,
+    mut events_sim: EventReader<SimEvent>,
+    mut query_player: Query<&mut AnimationPlayer, With<Rook>>,
+    res_rook: Res<RookRes>,
+    res_sim: Res<GameSim>,
+    res_map: Res<Map>
+){
+    for SimEvent(ev) in events_sim.iter(){
+        let (GameEvent::RocketBlocked { rook, .. } | GameEvent::BeamBlocked { rook, .. }) = ev else {continue};
+        let Some(rook) = res_sim.state.piece(*rook) else {continue};
+        let Some(occupant) = res_map.occupant_of(rook.pos, PieceKind::Rook) else {continue};
+        let Ok(mut player) = query_player.get_mut(occupant.entity) else {continue};
+        player.play(res_rook.block_animation.0.clone());
+        commands.entity(occupant.entity).insert((
+            BlockAnimToggle,
+            res_rook.block_animation.1.clone()
+        ));
+    }
+}
+
+pub fn rook_block_anim_is_end(
+    mut commands: Commands,
+    mut query_player: Query<(&mut AnimationPlayer, Entity), (With<BlockAnimToggle>, With<Rook>)>,
+    res_rook: Res<PieceRes<Rook>>,
+){
+    for (mut player, ent) in query_player.iter_mut(){
+        if player.elapsed() > 0.8{
+            let mut entity = commands.entity(ent);
+            entity.remove::<BlockAnimToggle>();
+            player.play(res_rook.idle_animation.0.clone()).repeat();
+            entity.insert(res_rook.idle_animation.1.clone());
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use bevy::{prelude::*, asset::{AssetLoader, LoadContext, LoadedAsset, LoadState}, reflect::{TypeUuid, TypePath}, utils::BoxedFuture};
+
+use super::simulation::GameRules;
+
+pub const RULES_PATH: &str = "game.rules.json";
+//기물 모델(glb)을 맞춰 만든 칸 크기, 규칙의 hex_size가 다르면 모델도 같은 비율로 늘리거나 줄임
+pub const MODEL_HEX_SIZE: f32 = 0.15;
+
+//게임 규칙 에셋, 로드할 때 검사를 통과한 규칙만 들어옴
+#[derive(Debug, TypeUuid, TypePath)]
+#[uuid = "9b811df3-6c10-488d-9a1a-de98b5a5a6c6"]
+pub struct RulesAsset(pub GameRules);
+
+#[derive(Default)]
+pub struct RulesLoader;
+
+impl AssetLoader for RulesLoader{
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let rules: GameRules = serde_json::from_slice(bytes)?;
+            rules.validate()?;
+            load_context.set_default_asset(LoadedAsset::new(RulesAsset(rules)));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["rules.json"]
+    }
+}
+
+//게임 규칙 리소스, 앱을 만들 때 미리 넣어두면 규칙 파일 대신 그 값을 씀
+#[derive(Resource, Clone, Deref)]
+pub struct RulesRes(pub GameRules);
+
+impl RulesRes{
+    //기물 모델에 곱하는 크기
+    pub fn model_scale(&self) -> f32{
+        self.hex_size / MODEL_HEX_SIZE
+    }
+}
+
+#[derive(Resource)]
+pub struct RulesHandle(pub Handle<RulesAsset>);
+
+pub fn setup_rules(
+    mut commands: Commands,
+    assets_server: Res<AssetServer>
+){
+    commands.insert_resource(RulesHandle(assets_server.load(RULES_PATH)));
+}
+
+//규칙 파일이 로드되면 RulesRes를 넣고, 없거나 잘못되었으면 기본 규칙을 씀 (웹 빌드도 같은 경로)
+pub fn rules_loaded_event(
+    mut commands: Commands,
+    assets_server: Res<AssetServer>,
+    res_rules: Res<Assets<RulesAsset>>,
+    res_handle: Res<RulesHandle>
+){
+    let rules = if let Some(rules) = res_rules.get(&res_handle.0){
+        rules.0.clone()
+    }else if assets_server.get_load_state(&res_handle.0) == LoadState::Failed{
+        warn!("{} could not be loaded, using the default rules", RULES_PATH);
+        GameRules::default()
+    }else{
+        return;
+    };
+    commands.insert_resource(RulesRes(rules));
+}
@@ -0,0 +1,123 @@
+use std::collections::HashSet;
+
+use hexx::Hex;
+use serde::{Deserialize, Serialize};
+
+use super::{PieceId, PieceKind, SpawnCategory, Team, Outcome};
+
+//플레이어(혹은 AI, 네트워크, 리플레이)가 게임에 요청하는 행동, 파일로 저장할 수 있음
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GameAction{
+    Combine{
+        team: Team,
+        base: Hex,
+        tiles: HashSet<Hex>,
+        category: Option<SpawnCategory> //여러 기물이 가능할 때 고른 기물
+    },
+    Launch{
+        team: Team,
+        from: Hex,
+        direction: Hex
+    },
+    Throw{
+        team: Team,
+        from: Hex,
+        target: Hex
+    },
+    Fire{
+        team: Team,
+        from: Hex,
+        direction: Hex
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ActionError{
+    NoCombination,
+    Ambiguous(Vec<SpawnCategory>), //여러 기물이 가능하니 하나를 골라야 함
+    NoPiece(Hex),
+    NotOwner(Hex), //다른 팀의 기물
+    NotLaunchable(Hex),
+    InvalidDirection(Hex),
+    OutOfRange(Hex),
+    NotReady(Hex),
+    GameOver
+}
+
+//공격한 기물(source)이 대상 기물(target)에게 주는 피해
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DamageEvent{
+    pub source: PieceId,
+    pub target: PieceId,
+    pub amount: u32
+}
+
+//시뮬레이션 결과로 발생하는 사건들, 렌더링 쪽은 이것만 보고 화면을 갱신함
+#[derive(Debug, Clone)]
+pub enum GameEvent{
+    PieceSpawned{
+        id: PieceId,
+        kind: PieceKind,
+        team: Team,
+        pos: Hex,
+        parent: Option<PieceId> //증식한 폰이라면 부모 폰
+    },
+    Combined{
+        team: Team,
+        base: Hex,
+        category: SpawnCategory,
+        consumed: Vec<(PieceId, Hex)>,
+        piece: Option<PieceId>
+    },
+    Launched{
+        from: Hex,
+        direction: Hex
+    },
+    RocketExploded{
+        id: PieceId,
+        pos: Hex
+    },
+    RocketBlocked{
+        id: PieceId,
+        pos: Hex,
+        rook: PieceId
+    },
+    Thrown{
+        id: PieceId,
+        from: Hex,
+        target: Hex
+    },
+    GrenadeExploded{
+        id: PieceId,
+        pos: Hex
+    },
+    Fired{
+        id: PieceId,
+        from: Hex
+    },
+    BeamBlocked{
+        id: PieceId,
+        pos: Hex,
+        rook: PieceId
+    },
+    BeamEnded{
+        id: PieceId,
+        pos: Hex
+    },
+    Damaged{
+        damage: DamageEvent,
+        hp: u32 //남은 체력, 0이면 바로 PieceDestroyed가 따라옴
+    },
+    PieceDestroyed{
+        id: PieceId,
+        kind: PieceKind,
+        team: Team,
+        pos: Hex
+    },
+    TeamEliminated{
+        team: Team
+    },
+    GameOver{
+        outcome: Outcome
+    }
+}
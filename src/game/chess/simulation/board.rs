@@ -0,0 +1,249 @@
+use std::collections::HashSet;
+
+use hexx::Hex;
+use serde::{Deserialize, Serialize};
+
+use super::{Team, MAX_TEAMS};
+
+//경로를 그릴 때 칸마다 어떻게 할지
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RayStep{
+    Mark, //경로에 넣고 계속 감
+    Skip, //표시하지 않고 지나감
+    Stop //여기서 끊김, 이 칸은 넣지 않음
+}
+
+//칸 사이의 기하 계산, 시뮬레이션(GameState)과 화면의 보드(Map)가 같은 계산을 씀
+pub trait HexBoard{
+    //보드 위의 칸 (바위 포함)
+    fn contains(&self, hex: Hex) -> bool;
+
+    //지나갈 수 없는 지형 (바위)
+    fn is_blocked(&self, hex: Hex) -> bool;
+
+    //기물이 있는 칸
+    fn is_occupied(&self, hex: Hex) -> bool;
+
+    //보드 안쪽이면서 지형에 막히지 않은 칸
+    fn is_passable(&self, hex: Hex) -> bool{
+        self.contains(hex) && !self.is_blocked(hex)
+    }
+
+    //지나갈 수 있으면서 아무것도 없는 칸
+    fn is_clear(&self, hex: Hex) -> bool{
+        self.is_passable(hex) && !self.is_occupied(hex)
+    }
+
+    //보드 위에 있는 이웃 칸들
+    fn neighbours(&self, hex: Hex) -> Vec<Hex>{
+        hex.all_neighbors().iter()
+            .copied()
+            .filter(|neighbour| self.contains(*neighbour))
+            .collect()
+    }
+
+    //center 에서 정확히 radius 만큼 떨어진 보드 위의 칸들
+    fn ring(&self, center: Hex, radius: u32) -> Vec<Hex>{
+        center.range(radius)
+            .filter(|hex| center.unsigned_distance_to(*hex) == radius && self.contains(*hex))
+            .collect()
+    }
+
+    //center 부터 radius 까지 가까운 고리 순서로 나열한 보드 위의 칸들
+    fn spiral(&self, center: Hex, radius: u32) -> Vec<Hex>{
+        (0..=radius).flat_map(|r| self.ring(center, r)).collect()
+    }
+
+    //from 에서 to 까지 곧게 이은 보드 위의 칸들, 양 끝을 포함함
+    fn line(&self, from: Hex, to: Hex) -> Vec<Hex>{
+        //같은 칸이면 hexx 의 line_to 가 거리 0으로 나누게 됨
+        let line: Vec<Hex> = if from == to {vec![from]} else {from.line_to(to).collect()};
+        line.into_iter().filter(|hex| self.contains(*hex)).collect()
+    }
+
+    //from 에서 direction 으로 보드 끝까지 걸으며 step 에 따라 칸을 모음
+    fn ray(&self, from: Hex, direction: Hex, mut step: impl FnMut(Hex) -> RayStep) -> Vec<Hex>{
+        let mut tiles = Vec::new();
+        let mut hex = from + direction;
+        while self.contains(hex){
+            match step(hex) {
+                RayStep::Mark => tiles.push(hex),
+                RayStep::Skip => {},
+                RayStep::Stop => break
+            }
+            hex += direction;
+        }
+        tiles
+    }
+}
+
+//보드 모양, 모양을 만든 뒤 중심이 (0, 0) 근처에 오도록 옮김
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BoardShape{
+    Hexagon{
+        radius: u32
+    },
+    Rectangle{
+        width: u32, //한 줄의 칸 수, 줄마다 반 칸씩 어긋나게 쌓음
+        height: u32
+    },
+    Parallelogram{
+        width: u32,
+        height: u32
+    },
+    Triangle{
+        size: u32 //한 변의 칸 수 - 1
+    },
+    Tiles(Vec<Hex>) //직접 적은 칸 목록, 옮기지 않음
+}
+
+impl Default for BoardShape{
+    fn default() -> Self {
+        BoardShape::Hexagon { radius: 10 }
+    }
+}
+
+impl BoardShape{
+    pub fn tiles(&self) -> Vec<Hex>{
+        match self {
+            BoardShape::Hexagon { radius } => Hex::ZERO.range(*radius).collect(),
+            BoardShape::Rectangle { width, height } => centered(
+                (0..*height as i32)
+                    .flat_map(|y| {
+                        let start = -(y / 2);
+                        (start..start + *width as i32).map(move |x| Hex::new(x, y))
+                    })
+                    .collect()
+            ),
+            BoardShape::Parallelogram { width, height } => centered(
+                (0..*height as i32)
+                    .flat_map(|y| (0..*width as i32).map(move |x| Hex::new(x, y)))
+                    .collect()
+            ),
+            BoardShape::Triangle { size } => centered(
+                (0..=*size as i32)
+                    .flat_map(|y| (0..=*size as i32 - y).map(move |x| Hex::new(x, y)))
+                    .collect()
+            ),
+            BoardShape::Tiles(tiles) => {
+                let mut unique = HashSet::with_capacity(tiles.len());
+                tiles.iter().copied().filter(|hex| unique.insert(*hex)).collect()
+            }
+        }
+    }
+}
+
+//칸들의 평균 위치
+pub fn board_center(tiles: &HashSet<Hex>) -> Hex{
+    if tiles.is_empty(){
+        return Hex::ZERO;
+    }
+    let len = tiles.len() as f32;
+    let (x, y) = tiles.iter().fold((0, 0), |(x, y), hex| (x + hex.x, y + hex.y));
+    Hex::new((x as f32 / len).round() as i32, (y as f32 / len).round() as i32)
+}
+
+fn centered(tiles: Vec<Hex>) -> Vec<Hex>{
+    let set: HashSet<Hex> = tiles.iter().copied().collect();
+    let center = board_center(&set);
+    tiles.into_iter().map(|hex| hex - center).collect()
+}
+
+//팀의 시작 방향, 6방향에 최대한 고르게 나눔
+pub fn start_direction(team: Team, team_count: usize) -> Hex{
+    let team_count = team_count.clamp(1, MAX_TEAMS);
+    let corner = team.0 as usize % team_count * 6 / team_count;
+    Hex::new(1, 0).rotate_cw(corner as u32)
+}
+
+//보드 중심에서 시작 방향으로 가장 멀리 나간 칸
+//같은 거리의 칸이 여럿이면 중심에 가까운 칸 (변의 가운데)을 고름
+pub fn farthest_tile(tiles: &HashSet<Hex>, center: Hex, direction: Hex) -> Option<Hex>{
+    let reach = |hex: Hex| {
+        let offset = hex - center;
+        //큐브 좌표의 내적
+        offset.x * direction.x + offset.y * direction.y + (-offset.x - offset.y) * (-direction.x - direction.y)
+    };
+    tiles.iter()
+        .copied()
+        .max_by_key(|hex| (reach(*hex), -(center.distance_to(*hex)), -hex.x, -hex.y))
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use super::super::{GameState, tests::board};
+
+    #[test]
+    fn neighbours_stay_on_the_board(){
+        let state = GameState::new(Hex::ZERO.range(2));
+        assert_eq!(state.neighbours(Hex::ZERO).len(), 6);
+        //모서리 칸은 이웃 셋이 보드 밖
+        assert_eq!(state.neighbours(Hex::new(2, 0)).len(), 3);
+        assert!(state.neighbours(Hex::new(2, 0)).iter().all(|hex| state.contains(*hex)));
+    }
+
+    #[test]
+    fn rings_and_spirals_are_cut_by_the_edge(){
+        let state = GameState::new(Hex::ZERO.range(2));
+        assert_eq!(state.ring(Hex::ZERO, 0), vec![Hex::ZERO]);
+        assert_eq!(state.ring(Hex::ZERO, 2).len(), 12);
+        assert_eq!(state.ring(Hex::new(2, 0), 1).len(), 3);
+        let spiral = state.spiral(Hex::ZERO, 2);
+        assert_eq!(spiral.len(), 19);
+        assert_eq!(spiral[0], Hex::ZERO);
+        assert!(spiral.windows(2).all(|w| w[0].unsigned_distance_to(Hex::ZERO) <= w[1].unsigned_distance_to(Hex::ZERO)));
+    }
+
+    #[test]
+    fn line_includes_both_ends_on_the_board(){
+        let state = GameState::new(Hex::ZERO.range(3));
+        let line = state.line(Hex::new(-3, 0), Hex::new(3, 0));
+        assert_eq!(line.len(), 7);
+        assert_eq!(line.first(), Some(&Hex::new(-3, 0)));
+        assert_eq!(line.last(), Some(&Hex::new(3, 0)));
+        assert_eq!(state.line(Hex::ZERO, Hex::ZERO), vec![Hex::ZERO]);
+        //보드 밖으로 나간 부분은 빠짐
+        assert_eq!(state.line(Hex::ZERO, Hex::new(5, 0)).len(), 4);
+    }
+
+    #[test]
+    fn start_positions_are_spread_over_the_corners(){
+        let starts = board().start_positions(2);
+        assert_eq!(starts, vec![
+            (Team::BLUE, Hex::new(5, 0), Some(Hex::new(4, 0))),
+            (Team::RED, Hex::new(-5, 0), Some(Hex::new(-4, 0)))
+        ]);
+        let corners: HashSet<Hex> = board().start_positions(6).iter().map(|(_, king, _)| *king).collect();
+        assert_eq!(corners.len(), 6);
+        assert!(corners.iter().all(|hex| Hex::ZERO.unsigned_distance_to(*hex) == 5));
+    }
+
+    #[test]
+    fn every_board_shape_gives_each_team_its_own_start(){
+        let shapes = [
+            BoardShape::Hexagon { radius: 3 },
+            BoardShape::Rectangle { width: 9, height: 6 },
+            BoardShape::Parallelogram { width: 7, height: 5 },
+            BoardShape::Triangle { size: 6 },
+            BoardShape::Tiles(vec![Hex::new(0, 0), Hex::new(1, 0), Hex::new(2, 0), Hex::new(3, 0), Hex::new(3, 0)])
+        ];
+        assert_eq!(shapes[0].tiles().len(), 37);
+        assert_eq!(shapes[1].tiles().len(), 54);
+        assert_eq!(shapes[3].tiles().len(), 28);
+        assert_eq!(shapes[4].tiles().len(), 4);
+        for shape in shapes.iter(){
+            let state = GameState::new(shape.tiles());
+            let center = board_center(state.tiles());
+            assert!(Hex::ZERO.unsigned_distance_to(center) <= 1 || matches!(shape, BoardShape::Tiles(_)));
+            let starts = state.start_positions(2);
+            assert_eq!(starts.len(), 2);
+            assert_ne!(starts[0].1, starts[1].1, "{:?}", shape);
+            for (_, king, pawn) in starts{
+                let pawn = pawn.unwrap();
+                assert!(state.tiles().contains(&king) && state.tiles().contains(&pawn));
+                assert_eq!(king.unsigned_distance_to(pawn), 1);
+            }
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use std::{collections::HashSet, fmt};
+
+use hexx::Hex;
+use serde::{Deserialize, Serialize};
+
+use super::PieceKind;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SpawnCategory{
+    Bishop,
+    Rook,
+    Knight,
+    Qean
+}
+
+impl SpawnCategory{
+    //조합으로 만들어지는 기물
+    pub fn kind(&self) -> PieceKind{
+        match self {
+            SpawnCategory::Bishop => PieceKind::Bishop,
+            SpawnCategory::Rook => PieceKind::Rook,
+            SpawnCategory::Knight => PieceKind::Knight,
+            SpawnCategory::Qean => PieceKind::Queen
+        }
+    }
+}
+
+//폰 조합 규칙, assets/combination.recipes.json 에서 읽어옴
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recipe{
+    pub category: SpawnCategory,
+    pub cost: usize, //선택해야 하는 폰 수 (기준 칸 포함), 선택된 폰은 모두 소모됨
+    pub shapes: Vec<Vec<Hex>>, //기준 칸에서의 상대 위치, 한 방향만 적으면 나머지 방향은 회전해서 만듦
+    #[serde(default)]
+    pub mirror: bool //좌우로 뒤집은 모양도 허용
+}
+
+//잘못 적힌 조합 규칙, 어느 기물의 규칙인지 같이 알려줌
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecipeError{
+    NoShapes(SpawnCategory),
+    ShapeSize(SpawnCategory, usize, usize), //(비용, 모양의 칸 수)
+    ZeroOffset(SpawnCategory),
+    DuplicateOffset(SpawnCategory, Hex)
+}
+
+impl fmt::Display for RecipeError{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecipeError::NoShapes(category) => write!(f, "recipe {:?} has no shapes", category),
+            RecipeError::ShapeSize(category, cost, len) =>
+                write!(f, "recipe {:?}: cost {} needs {} offsets, a shape has {}", category, cost, cost.saturating_sub(1), len),
+            RecipeError::ZeroOffset(category) =>
+                write!(f, "recipe {:?}: the base tile (0, 0) must not be listed as an offset", category),
+            RecipeError::DuplicateOffset(category, hex) =>
+                write!(f, "recipe {:?}: offset ({}, {}) is listed twice", category, hex.x, hex.y)
+        }
+    }
+}
+
+impl std::error::Error for RecipeError{}
+
+impl Recipe{
+    //선택한 폰이 기준 칸과 모양의 칸으로 정확히 이루어져야 함
+    pub fn matches(&self, base_hex: Hex, hex_list: &HashSet<Hex>) -> bool{
+        if hex_list.len() != self.cost || !hex_list.contains(&base_hex){
+            return false;
+        }
+        self.variants().iter()
+            .filter(|shape| shape.len() + 1 == self.cost)
+            .any(|shape| shape.iter().all(|offset| hex_list.contains(&(base_hex + *offset))))
+    }
+
+    //모양마다 기준 칸을 뺀 서로 다른 cost - 1개의 칸이 있어야 함
+    pub fn validate(&self) -> Result<(), RecipeError>{
+        if self.shapes.is_empty(){
+            return Err(RecipeError::NoShapes(self.category));
+        }
+        for shape in self.shapes.iter(){
+            if shape.len() + 1 != self.cost{
+                return Err(RecipeError::ShapeSize(self.category, self.cost, shape.len()));
+            }
+            let mut seen = HashSet::with_capacity(shape.len());
+            for offset in shape.iter(){
+                if *offset == Hex::ZERO{
+                    return Err(RecipeError::ZeroOffset(self.category));
+                }
+                if !seen.insert(*offset){
+                    return Err(RecipeError::DuplicateOffset(self.category, *offset));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    //회전, 뒤집기로 만든 모든 모양
+    pub fn variants(&self) -> Vec<Vec<Hex>>{
+        let mut variants = Vec::new();
+        for shape in self.shapes.iter(){
+            for variant in expand_shape(shape, self.mirror){
+                if !variants.contains(&variant){
+                    variants.push(variant);
+                }
+            }
+        }
+        variants
+    }
+}
+
+//기준 모양을 6방향으로 회전시킨 변형들 (mirror면 뒤집은 모양의 회전까지 최대 12가지)
+//같은 칸들로 이루어진 변형은 하나만 남김
+pub fn expand_shape(shape: &[Hex], mirror: bool) -> Vec<Vec<Hex>>{
+    let mut bases = vec![shape.to_vec()];
+    if mirror{
+        bases.push(shape.iter().map(|hex| mirror_hex(*hex)).collect());
+    }
+    let mut variants: Vec<Vec<Hex>> = Vec::with_capacity(bases.len() * 6);
+    for base in bases.iter(){
+        for rotation in 0..6{
+            let mut variant: Vec<Hex> = base.iter().map(|hex| hex.rotate_cw(rotation)).collect();
+            variant.sort_by_key(|hex| (hex.x, hex.y));
+            if !variants.contains(&variant){
+                variants.push(variant);
+            }
+        }
+    }
+    variants
+}
+
+//x축(q축)을 기준으로 뒤집음, 큐브 좌표의 r과 s를 바꾸는 것과 같음
+pub fn mirror_hex(hex: Hex) -> Hex{
+    Hex::new(hex.x, -hex.x - hex.y)
+}
+
+//에셋이 아직 로드되지 않았을 때와 테스트에서 쓰는 기본 규칙
+pub fn default_recipes() -> Vec<Recipe>{
+    serde_json::from_str(include_str!("../../../../assets/combination.recipes.json"))
+        .expect("assets/combination.recipes.json is not a valid recipe list")
+}
+
+//선택된 폰들로 만들 수 있는 모든 기물 (규칙 목록의 순서대로)
+pub fn find_combinations(
+    recipes: &[Recipe],
+    base_hex: Hex,
+    hex_list: &HashSet<Hex>
+) -> Vec<SpawnCategory>{
+    let mut categories: Vec<SpawnCategory> = Vec::new();
+    for recipe in recipes.iter().filter(|recipe| recipe.matches(base_hex, hex_list)){
+        if !categories.contains(&recipe.category){
+            categories.push(recipe.category);
+        }
+    }
+    categories
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+    use super::super::{GameAction, ActionError, Team, tests::board};
+
+    fn recipe(category: SpawnCategory) -> Recipe{
+        default_recipes().into_iter().find(|r| r.category == category).unwrap()
+    }
+
+    #[test]
+    fn variant_counts(){
+        assert_eq!(recipe(SpawnCategory::Bishop).variants().len(), 18);
+        assert_eq!(recipe(SpawnCategory::Rook).variants().len(), 6);
+        assert_eq!(recipe(SpawnCategory::Knight).variants().len(), 12);
+        assert_eq!(recipe(SpawnCategory::Qean).variants().len(), 6);
+    }
+
+    #[test]
+    fn variants_are_closed_under_rotation_and_mirror(){
+        for recipe in default_recipes(){
+            let variants = recipe.variants();
+            for variant in variants.iter(){
+                for rotation in 0..6{
+                    let mut rotated: Vec<Hex> = variant.iter().map(|hex| hex.rotate_cw(rotation)).collect();
+                    rotated.sort_by_key(|hex| (hex.x, hex.y));
+                    assert!(variants.contains(&rotated), "{:?} is missing {:?}", recipe.category, rotated);
+                }
+                if recipe.mirror{
+                    let mut mirrored: Vec<Hex> = variant.iter().map(|hex| mirror_hex(*hex)).collect();
+                    mirrored.sort_by_key(|hex| (hex.x, hex.y));
+                    assert!(variants.contains(&mirrored), "{:?} is missing {:?}", recipe.category, mirrored);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn mirror_is_an_involution_that_keeps_distance(){
+        for hex in Hex::ZERO.range(3){
+            assert_eq!(mirror_hex(mirror_hex(hex)), hex);
+            assert_eq!(Hex::ZERO.distance_to(mirror_hex(hex)), Hex::ZERO.distance_to(hex));
+        }
+    }
+
+    #[test]
+    fn knight_matches_every_direction(){
+        let knight = recipe(SpawnCategory::Knight);
+        for direction in Hex::ZERO.all_neighbors().iter(){
+            let tiles: HashSet<Hex> = [Hex::ZERO, *direction, *direction + *direction, *direction + *direction + direction.rotate_cw(1)]
+                .iter().copied().collect();
+            assert!(knight.matches(Hex::ZERO, &tiles));
+            let tiles: HashSet<Hex> = [Hex::ZERO, *direction, *direction + *direction, *direction + *direction + direction.rotate_ccw(1)]
+                .iter().copied().collect();
+            assert!(knight.matches(Hex::ZERO, &tiles));
+        }
+    }
+
+    #[test]
+    fn recipe_cost_must_match_the_selection(){
+        let mut state = board();
+        let rook = Recipe{
+            category: SpawnCategory::Rook,
+            cost: 2,
+            shapes: vec![vec![Hex::new(1, 0)]],
+            mirror: false
+        };
+        assert_eq!(rook.validate(), Ok(()));
+        state.set_recipes(vec![rook]);
+        let stray = Hex::new(-3, 0);
+        for hex in [Hex::ZERO, Hex::new(1, 0), stray].iter(){
+            state.place(PieceKind::Pawn, Team::BLUE, *hex);
+        }
+        //모양에 없는 폰이 섞이면 조합되지 않고 아무것도 소모하지 않음
+        let tiles: HashSet<Hex> = [Hex::ZERO, Hex::new(1, 0), stray].iter().copied().collect();
+        let result = state.apply(GameAction::Combine { team: Team::BLUE, base: Hex::ZERO, tiles, category: None });
+        assert_eq!(result, Err(ActionError::NoCombination));
+        assert_eq!(state.team_pawns(Team::BLUE).len(), 3);
+
+        let tiles: HashSet<Hex> = [Hex::ZERO, Hex::new(1, 0)].iter().copied().collect();
+        state.apply(GameAction::Combine { team: Team::BLUE, base: Hex::ZERO, tiles, category: None }).unwrap();
+        assert_eq!(state.piece_at(Hex::ZERO).unwrap().kind, PieceKind::Rook);
+        assert_eq!(state.team_pawns(Team::BLUE), [stray].iter().copied().collect::<HashSet<Hex>>());
+    }
+
+    #[test]
+    fn recipe_shapes_must_fit_the_cost(){
+        let shape = |offsets: &[Hex]| Recipe{ category: SpawnCategory::Knight, cost: 3, shapes: vec![offsets.to_vec()], mirror: false };
+        assert_eq!(shape(&[Hex::new(1, 0)]).validate(), Err(RecipeError::ShapeSize(SpawnCategory::Knight, 3, 1)));
+        assert_eq!(shape(&[Hex::new(1, 0), Hex::ZERO]).validate(), Err(RecipeError::ZeroOffset(SpawnCategory::Knight)));
+        assert_eq!(
+            shape(&[Hex::new(1, 0), Hex::new(1, 0)]).validate(),
+            Err(RecipeError::DuplicateOffset(SpawnCategory::Knight, Hex::new(1, 0)))
+        );
+        for recipe in default_recipes(){
+            assert_eq!(recipe.validate(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn ambiguous_combination_waits_for_a_choice(){
+        let mut state = board();
+        let line = vec![vec![Hex::new(1, 0), Hex::new(2, 0)]];
+        state.set_recipes(vec![
+            Recipe{ category: SpawnCategory::Bishop, cost: 3, shapes: line.clone(), mirror: false },
+            Recipe{ category: SpawnCategory::Rook, cost: 3, shapes: line, mirror: false }
+        ]);
+        let tiles: HashSet<Hex> = [Hex::new(0, 0), Hex::new(1, 0), Hex::new(2, 0)].iter().copied().collect();
+        for hex in tiles.iter(){
+            state.place(PieceKind::Pawn, Team::BLUE, *hex);
+        }
+        let result = state.apply(GameAction::Combine { team: Team::BLUE, base: Hex::ZERO, tiles: tiles.clone(), category: None });
+        assert_eq!(result, Err(ActionError::Ambiguous(vec![SpawnCategory::Bishop, SpawnCategory::Rook])));
+        assert_eq!(state.team_pawns(Team::BLUE).len(), 3);
+
+        let result = state.apply(GameAction::Combine {
+            team: Team::BLUE,
+            base: Hex::ZERO,
+            tiles: tiles.clone(),
+            category: Some(SpawnCategory::Knight)
+        });
+        assert_eq!(result, Err(ActionError::NoCombination));
+        state.apply(GameAction::Combine { team: Team::BLUE, base: Hex::ZERO, tiles, category: Some(SpawnCategory::Rook) }).unwrap();
+        assert_eq!(state.piece_at(Hex::ZERO).unwrap().kind, PieceKind::Rook);
+    }
+}
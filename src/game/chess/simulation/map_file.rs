@@ -0,0 +1,223 @@
+use std::{collections::{HashMap, HashSet}, fmt};
+
+use hexx::Hex;
+use serde::{Deserialize, Serialize};
+
+use super::{BoardShape, PieceKind, Team, MAX_TEAMS};
+
+//지금 읽을 수 있는 맵 파일 버전, 형식이 바뀌면 올림
+pub const MAP_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TileKind{
+    #[default]
+    Normal,
+    Blocked, //바위, 아무것도 지나가거나 놓일 수 없음
+    Special //눈에 띄게 표시만 하는 칸
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TileDef{
+    pub x: i32,
+    pub y: i32,
+    #[serde(default)]
+    pub kind: TileKind
+}
+
+//팀의 왕이 놓이는 칸
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartDef{
+    pub team: Team,
+    pub x: i32,
+    pub y: i32
+}
+
+//시작할 때 미리 놓아두는 기물
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PieceDef{
+    pub team: Team,
+    pub kind: PieceKind,
+    pub x: i32,
+    pub y: i32
+}
+
+//손으로 만드는 보드 파일 (assets/maps/*.map.json)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MapFile{
+    pub version: u32,
+    pub tiles: Vec<TileDef>,
+    #[serde(default)]
+    pub starts: Vec<StartDef>, //비어 있으면 보드 모양으로 정함
+    #[serde(default)]
+    pub pieces: Vec<PieceDef>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapError{
+    UnsupportedVersion(u32),
+    Empty,
+    DuplicateTile(Hex),
+    StartOffBoard(Team, Hex),
+    StartBlocked(Team, Hex),
+    DuplicateStart(Team),
+    TooManyTeams(Team),
+    PieceOffBoard(Hex),
+    PieceBlocked(Hex),
+    Overlap(Hex), //시작 위치나 기물이 같은 칸에 겹침
+    MissingStart(Team), //참가하는 팀인데 시작 위치가 없음
+    TeamNotPlaying(Team) //규칙의 팀 수보다 뒤의 팀
+}
+
+impl fmt::Display for MapError{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapError::UnsupportedVersion(version) =>
+                write!(f, "unsupported map version {} (expected {})", version, MAP_VERSION),
+            MapError::Empty => write!(f, "map has no tiles"),
+            MapError::DuplicateTile(hex) => write!(f, "tile ({}, {}) is listed twice", hex.x, hex.y),
+            MapError::StartOffBoard(team, hex) =>
+                write!(f, "tile ({}, {}): start of team {} is not on the board", hex.x, hex.y, team.0),
+            MapError::StartBlocked(team, hex) =>
+                write!(f, "tile ({}, {}): start of team {} is a blocked tile", hex.x, hex.y, team.0),
+            MapError::DuplicateStart(team) => write!(f, "team {} has more than one start", team.0),
+            MapError::TooManyTeams(team) => write!(f, "team {} is out of range (max {} teams)", team.0, MAX_TEAMS),
+            MapError::PieceOffBoard(hex) => write!(f, "tile ({}, {}): piece is not on the board", hex.x, hex.y),
+            MapError::PieceBlocked(hex) => write!(f, "tile ({}, {}): piece is on a blocked tile", hex.x, hex.y),
+            MapError::Overlap(hex) => write!(f, "tile ({}, {}): more than one piece starts here", hex.x, hex.y),
+            MapError::MissingStart(team) => write!(f, "team {} has no start", team.0),
+            MapError::TeamNotPlaying(team) => write!(f, "team {} is not in this match", team.0)
+        }
+    }
+}
+
+impl std::error::Error for MapError{}
+
+impl MapFile{
+    //맵 파일이 없을 때 규칙의 보드 모양과 바위로 만든 맵
+    pub fn from_shape(shape: &BoardShape, obstacles: &[Hex]) -> Self{
+        let tiles = shape.tiles().into_iter()
+            .map(|hex| TileDef{
+                x: hex.x,
+                y: hex.y,
+                kind: if obstacles.contains(&hex) {TileKind::Blocked} else {TileKind::Normal}
+            })
+            .collect();
+        Self { version: MAP_VERSION, tiles, starts: Vec::new(), pieces: Vec::new() }
+    }
+
+    pub fn tiles_of(&self, kind: TileKind) -> HashSet<Hex>{
+        self.tiles.iter()
+            .filter(|tile| tile.kind == kind)
+            .map(|tile| Hex::new(tile.x, tile.y))
+            .collect()
+    }
+
+    //처음 발견한 문제 하나를 알려줌
+    pub fn validate(&self) -> Result<(), MapError>{
+        if self.version != MAP_VERSION{
+            return Err(MapError::UnsupportedVersion(self.version));
+        }
+        if self.tiles.is_empty(){
+            return Err(MapError::Empty);
+        }
+        let mut kinds = HashMap::with_capacity(self.tiles.len());
+        for tile in self.tiles.iter(){
+            let hex = Hex::new(tile.x, tile.y);
+            if kinds.insert(hex, tile.kind).is_some(){
+                return Err(MapError::DuplicateTile(hex));
+            }
+        }
+
+        let mut teams = HashSet::new();
+        let mut occupied = HashSet::new();
+        for start in self.starts.iter(){
+            let hex = Hex::new(start.x, start.y);
+            if start.team.0 as usize >= MAX_TEAMS{
+                return Err(MapError::TooManyTeams(start.team));
+            }
+            match kinds.get(&hex) {
+                None => return Err(MapError::StartOffBoard(start.team, hex)),
+                Some(TileKind::Blocked) => return Err(MapError::StartBlocked(start.team, hex)),
+                Some(_) => {}
+            }
+            if !teams.insert(start.team){
+                return Err(MapError::DuplicateStart(start.team));
+            }
+            if !occupied.insert(hex){
+                return Err(MapError::Overlap(hex));
+            }
+        }
+        for piece in self.pieces.iter(){
+            let hex = Hex::new(piece.x, piece.y);
+            if piece.team.0 as usize >= MAX_TEAMS{
+                return Err(MapError::TooManyTeams(piece.team));
+            }
+            match kinds.get(&hex) {
+                None => return Err(MapError::PieceOffBoard(hex)),
+                Some(TileKind::Blocked) => return Err(MapError::PieceBlocked(hex)),
+                Some(_) => {}
+            }
+            if !occupied.insert(hex){
+                return Err(MapError::Overlap(hex));
+            }
+        }
+        Ok(())
+    }
+
+    //규칙의 팀 수와 맞는지, 시작 위치가 없는 맵은 보드 모양으로 정하므로 기물만 봄
+    pub fn check_players(&self, players: usize) -> Result<(), MapError>{
+        if !self.starts.is_empty(){
+            for team in Team::all(players){
+                if !self.starts.iter().any(|start| start.team == team){
+                    return Err(MapError::MissingStart(team));
+                }
+            }
+        }
+        let teams = self.starts.iter().map(|start| start.team)
+            .chain(self.pieces.iter().map(|piece| piece.team));
+        for team in teams{
+            if team.0 as usize >= players{
+                return Err(MapError::TeamNotPlaying(team));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn map_validation_points_at_the_bad_tile(){
+        let map: MapFile = serde_json::from_str(include_str!("../../../../assets/maps/duel.map.json")).unwrap();
+        assert_eq!(map.validate(), Ok(()));
+
+        let mut broken = map.clone();
+        broken.version = MAP_VERSION + 1;
+        assert_eq!(broken.validate(), Err(MapError::UnsupportedVersion(MAP_VERSION + 1)));
+
+        let blocked = map.tiles_of(TileKind::Blocked).into_iter().next().unwrap();
+        let mut broken = map.clone();
+        broken.pieces.push(PieceDef { team: Team::RED, kind: PieceKind::Rook, x: blocked.x, y: blocked.y });
+        assert_eq!(broken.validate(), Err(MapError::PieceBlocked(blocked)));
+        assert!(broken.validate().unwrap_err().to_string().starts_with(&format!("tile ({}, {})", blocked.x, blocked.y)));
+
+        let mut broken = map.clone();
+        broken.starts.push(StartDef { team: Team(2), x: 40, y: 0 });
+        assert_eq!(broken.validate(), Err(MapError::StartOffBoard(Team(2), Hex::new(40, 0))));
+
+        let mut broken = map.clone();
+        let tile = broken.tiles[0].clone();
+        broken.tiles.push(tile);
+        assert!(matches!(broken.validate(), Err(MapError::DuplicateTile(_))));
+
+        //시작 위치가 있는 맵은 참가하는 팀마다 하나씩 있어야 함
+        assert_eq!(map.check_players(2), Ok(()));
+        assert_eq!(map.check_players(3), Err(MapError::MissingStart(Team(2))));
+        let mut broken = map;
+        broken.starts.retain(|start| start.team == Team::BLUE);
+        assert_eq!(broken.check_players(1), Err(MapError::TeamNotPlaying(Team::RED)));
+        assert_eq!(MapFile::from_shape(&BoardShape::default(), &[]).check_players(MAX_TEAMS), Ok(()));
+    }
+}
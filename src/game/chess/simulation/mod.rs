@@ -0,0 +1,999 @@
+//bevy에 의존하지 않는 게임 규칙 시뮬레이션
+//보드 점유, 기물, 팀, 타이머를 모두 여기서 관리하고 bevy 시스템은 결과(GameEvent)만 그려줌
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use hexx::Hex;
+
+mod action;
+mod board;
+mod combination;
+mod map_file;
+mod piece;
+mod replay;
+mod rng;
+mod rules;
+mod team;
+
+pub use action::*;
+pub use board::*;
+pub use combination::*;
+pub use map_file::*;
+pub use piece::*;
+pub use replay::*;
+pub use rng::*;
+pub use rules::*;
+pub use team::*;
+
+//GameRules의 기본값
+pub const PLAYERS: usize = 2; //한 판에 참가하는 팀 수
+pub const PAWN_SPAWN_TIME_MIN: f32 = 2.8;
+pub const PAWN_SPAWN_TIME_MAX: f32 = 3.2;
+pub const PAWN_CAP: usize = 40; //팀마다 보드에 둘 수 있는 최대 폰 수
+pub const ROCKET_START_SPEED: f32 = 1.;
+pub const ROCKET_ACCELERATION: f32 = 1.;
+pub const KNIGHT_RANGE_MIN: u32 = 2;
+pub const KNIGHT_RANGE_MAX: u32 = 4;
+pub const KNIGHT_RELOAD_TIME: f32 = 3.;
+pub const GRENADE_FLIGHT_TIME: f32 = 1.2;
+pub const GRENADE_RADIUS: u32 = 1;
+pub const QUEEN_RELOAD_TIME: f32 = 5.;
+pub const BEAM_SPEED: f32 = 12.; //초당 칸 수
+pub const ROCKET_DAMAGE: u32 = 3;
+pub const GRENADE_DAMAGE: u32 = 2;
+pub const BEAM_DAMAGE: u32 = 2;
+
+#[derive(Debug, Default)]
+pub struct GameState{
+    tiles: HashSet<Hex>,
+    blocked: HashSet<Hex>, //지나갈 수 없는 지형 (바위)
+    //id 순서로 순회해야 같은 시드에서 항상 같은 결과가 나옴
+    pieces: BTreeMap<PieceId, Piece>,
+    board: HashMap<Hex, PieceId>,
+    rockets: BTreeMap<PieceId, Rocket>,
+    grenades: BTreeMap<PieceId, Grenade>,
+    beams: BTreeMap<PieceId, Beam>,
+    recipes: Vec<Recipe>,
+    rules: GameRules,
+    rng: GameRng,
+    events: Vec<GameEvent>,
+    next_id: u32,
+    eliminated: Vec<Team>, //이번 틱에 왕을 잃은 팀, 틱이 끝날 때 승패를 정함
+    outcome: Option<Outcome>
+}
+
+impl GameState{
+    pub fn new(tiles: impl IntoIterator<Item = Hex>) -> Self{
+        Self::with_seed(tiles, rand::random())
+    }
+
+    pub fn with_seed(tiles: impl IntoIterator<Item = Hex>, seed: u64) -> Self{
+        Self{
+            tiles: tiles.into_iter().collect(),
+            recipes: default_recipes(),
+            rng: GameRng::new(seed),
+            ..Default::default()
+        }
+    }
+
+    pub fn rules(&self) -> &GameRules{
+        &self.rules
+    }
+
+    //시작할 때 규칙 교체, 이미 놓인 폰의 증식 주기는 그대로임
+    pub fn set_rules(&mut self, rules: GameRules){
+        self.rules = rules;
+    }
+
+    pub fn seed(&self) -> u64{
+        self.rng.seed()
+    }
+
+    pub fn outcome(&self) -> Option<Outcome>{
+        self.outcome
+    }
+
+    pub fn recipes(&self) -> &[Recipe]{
+        &self.recipes
+    }
+
+    //조합 규칙 교체 (에셋이 로드되거나 수정되었을 때)
+    pub fn set_recipes(&mut self, recipes: Vec<Recipe>){
+        self.recipes = recipes;
+    }
+
+    //보드 밖의 칸은 무시함
+    pub fn set_blocked(&mut self, blocked: impl IntoIterator<Item = Hex>){
+        self.blocked = blocked.into_iter()
+            .filter(|hex| self.tiles.contains(hex))
+            .collect();
+    }
+
+    pub fn piece(&self, id: PieceId) -> Option<&Piece>{
+        self.pieces.get(&id)
+    }
+
+    pub fn piece_at(&self, hex: Hex) -> Option<&Piece>{
+        self.board.get(&hex).and_then(|id| self.pieces.get(id))
+    }
+
+    pub fn pieces(&self) -> impl Iterator<Item = &Piece>{
+        self.pieces.values()
+    }
+
+    pub fn rocket(&self, id: PieceId) -> Option<&Rocket>{
+        self.rockets.get(&id)
+    }
+
+    pub fn grenade(&self, id: PieceId) -> Option<&Grenade>{
+        self.grenades.get(&id)
+    }
+
+    pub fn beam(&self, id: PieceId) -> Option<&Beam>{
+        self.beams.get(&id)
+    }
+
+    //나이트가 from에서 유탄을 던질 수 있는 칸들
+    pub fn throw_targets(&self, from: Hex) -> Vec<Hex>{
+        let (range_min, range_max) = self.rules.knight_range;
+        (range_min..=range_max)
+            .flat_map(|radius| self.ring(from, radius))
+            .filter(|hex| self.is_passable(*hex))
+            .collect()
+    }
+
+    //hex 자신이나 이웃칸에 있는 적 록, 로켓을 막아줌
+    pub fn shield_at(&self, hex: Hex, team: Team) -> Option<PieceId>{
+        self.spiral(hex, 1).into_iter()
+            .filter_map(|h| self.piece_at(h))
+            .find(|p| p.kind == PieceKind::Rook && p.team != team)
+            .map(|p| p.id)
+    }
+
+    pub fn pawn_count(&self, team: Team) -> usize{
+        self.pieces.values()
+            .filter(|p| p.kind == PieceKind::Pawn && p.team == team)
+            .count()
+    }
+
+    //빈 이웃 칸이 하나도 없는 폰은 증식 타이머가 멈춤
+    pub fn is_boxed_in(&self, hex: Hex) -> bool{
+        self.neighbours(hex).into_iter().all(|neighbour| !self.is_clear(neighbour))
+    }
+
+    //각 팀의 (왕 칸, 첫 폰 칸), 보드 모양에 맞춰 팀의 시작 방향으로 가장 먼 칸에 왕을 두고
+    //그 이웃 중 보드 중심에 가장 가까운 칸에 폰을 둠
+    pub fn start_positions(&self, team_count: usize) -> Vec<(Team, Hex, Option<Hex>)>{
+        let center = board_center(&self.tiles);
+        let passable: HashSet<Hex> = self.tiles.iter()
+            .copied()
+            .filter(|hex| self.is_passable(*hex))
+            .collect();
+        Team::all(team_count)
+            .filter_map(|team| {
+                let king = farthest_tile(&passable, center, start_direction(team, team_count))?;
+                Some((team, king, self.start_pawn(king)))
+            })
+            .collect()
+    }
+
+    //왕 옆의 빈 칸 중 보드 중심에 가장 가까운 칸
+    pub fn start_pawn(&self, king: Hex) -> Option<Hex>{
+        let center = board_center(&self.tiles);
+        self.neighbours(king).into_iter()
+            .filter(|hex| self.is_clear(*hex))
+            .min_by_key(|hex| (center.distance_to(*hex), hex.x, hex.y))
+    }
+
+    //아직 보드에 기물이 남아있는 팀들
+    pub fn alive_teams(&self) -> Vec<Team>{
+        let mut teams: Vec<Team> = self.pieces.values()
+            .map(|p| p.team)
+            .collect();
+        teams.sort();
+        teams.dedup();
+        teams
+    }
+
+    //선택한 칸들로 만들 수 있는 기물들, 실제로 폰을 소모하지는 않음
+    pub fn preview_combinations(&self, team: Team, base: Hex, tiles: &HashSet<Hex>) -> Vec<SpawnCategory>{
+        let hex_list = self.own_pawns_in(team, tiles);
+        if !hex_list.contains(&base){
+            return Vec::new();
+        }
+        find_combinations(&self.recipes, base, &hex_list)
+    }
+
+    //게임 시작 배치 같은 규칙 외부의 배치
+    pub fn place(&mut self, kind: PieceKind, team: Team, pos: Hex) -> Option<PieceId>{
+        if !self.is_clear(pos){
+            return None;
+        }
+        let id = self.insert_piece(kind, team, pos);
+        self.events.push(GameEvent::PieceSpawned { id, kind, team, pos, parent: None });
+        Some(id)
+    }
+
+    //모든 입력(플레이어, AI, 리플레이)은 여기서 검사하고 적용함
+    pub fn apply(&mut self, action: GameAction) -> Result<(), ActionError>{
+        if self.outcome.is_some(){
+            return Err(ActionError::GameOver);
+        }
+        match action {
+            GameAction::Combine { team, base, tiles, category } => self.combine(team, base, tiles, category),
+            GameAction::Launch { team, from, direction } => self.launch(team, from, direction),
+            GameAction::Throw { team, from, target } => self.throw(team, from, target),
+            GameAction::Fire { team, from, direction } => self.fire(team, from, direction)
+        }
+    }
+
+    pub fn tick(&mut self, delta: f32){
+        if self.outcome.is_some(){
+            return;
+        }
+        self.tick_pawns(delta);
+        self.tick_cooldowns(delta);
+        self.tick_rockets(delta);
+        self.tick_grenades(delta);
+        self.tick_beams(delta);
+        self.settle_outcome();
+    }
+
+    pub fn drain_events(&mut self) -> Vec<GameEvent>{
+        std::mem::take(&mut self.events)
+    }
+
+    fn next_id(&mut self) -> PieceId{
+        let id = PieceId(self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    fn insert_piece(&mut self, kind: PieceKind, team: Team, pos: Hex) -> PieceId{
+        let id = self.next_id();
+        let (spawn_min, spawn_max) = self.rules.pawn_spawn_time;
+        //최소와 최대가 같으면 고정 주기
+        let spawn_time = self.rng.gen_range(spawn_min..=spawn_max);
+        self.pieces.insert(id, Piece{
+            id,
+            kind,
+            team,
+            pos,
+            hp: kind.max_hp(),
+            spawn_time,
+            spawn_elapsed: 0.,
+            cooldown: 0.
+        });
+        self.board.insert(pos, id);
+        id
+    }
+
+    fn remove_piece(&mut self, id: PieceId) -> Option<Piece>{
+        let piece = self.pieces.remove(&id)?;
+        if self.board.get(&piece.pos) == Some(&id){
+            self.board.remove(&piece.pos);
+        }
+        Some(piece)
+    }
+
+    //체력이 다 떨어지면 기물을 제거함
+    fn damage(&mut self, damage: DamageEvent){
+        if self.outcome.is_some(){
+            return;
+        }
+        let Some(piece) = self.pieces.get_mut(&damage.target) else {return};
+        piece.hp = piece.hp.saturating_sub(damage.amount);
+        let hp = piece.hp;
+        self.events.push(GameEvent::Damaged { damage, hp });
+        if hp == 0{
+            self.destroy_piece(damage.target);
+        }
+    }
+
+    fn destroy_piece(&mut self, id: PieceId){
+        let Some(piece) = self.remove_piece(id) else {return};
+        self.events.push(GameEvent::PieceDestroyed {
+            id,
+            kind: piece.kind,
+            team: piece.team,
+            pos: piece.pos
+        });
+        //왕이 잡히면 그 팀은 탈락하고 남은 기물도 모두 사라짐
+        let king_left = self.pieces.values().any(|p| p.kind == PieceKind::King && p.team == piece.team);
+        if piece.kind == PieceKind::King && !king_left{
+            self.eliminate(piece.team);
+        }
+    }
+
+    fn eliminate(&mut self, team: Team){
+        self.events.push(GameEvent::TeamEliminated { team });
+        let ids: Vec<PieceId> = self.pieces.values()
+            .filter(|p| p.team == team)
+            .map(|p| p.id)
+            .collect();
+        for id in ids{
+            self.destroy_piece(id);
+        }
+        self.eliminated.push(team);
+    }
+
+    //같은 틱에 여러 왕이 잡힐 수 있으므로 틱이 끝난 뒤에 한번만 정함
+    fn settle_outcome(&mut self){
+        if std::mem::take(&mut self.eliminated).is_empty(){
+            return;
+        }
+        //마지막 한 팀만 남으면 그 팀의 승리, 아무도 없으면 무승부
+        let alive = self.alive_teams();
+        let outcome = match alive.as_slice() {
+            [] => Outcome::Draw,
+            [winner] => Outcome::Winner(*winner),
+            _ => return
+        };
+        self.outcome = Some(outcome);
+        self.events.push(GameEvent::GameOver { outcome });
+    }
+
+    fn own_pawns_in(&self, team: Team, tiles: &HashSet<Hex>) -> HashSet<Hex>{
+        tiles.iter()
+            .copied()
+            .filter(|hex| {
+                self.piece_at(*hex)
+                    .map_or(false, |p| p.kind == PieceKind::Pawn && p.team == team)
+            })
+            .collect()
+    }
+
+    fn combine(
+        &mut self,
+        team: Team,
+        base: Hex,
+        tiles: HashSet<Hex>,
+        choice: Option<SpawnCategory>
+    ) -> Result<(), ActionError>{
+        let mut hex_list: Vec<Hex> = self.own_pawns_in(team, &tiles).into_iter().collect();
+        hex_list.sort_by_key(|hex| (hex.x, hex.y));
+        let categories = self.preview_combinations(team, base, &tiles);
+        //고른 기물이 없는데 여러 기물이 가능하면 폰을 소모하지 않고 되돌려줌
+        let category = match (choice, categories.as_slice()) {
+            (_, []) => return Err(ActionError::NoCombination),
+            (Some(choice), _) if categories.contains(&choice) => choice,
+            (Some(_), _) => return Err(ActionError::NoCombination),
+            (None, [category]) => *category,
+            (None, _) => return Err(ActionError::Ambiguous(categories))
+        };
+
+        let mut consumed = Vec::with_capacity(hex_list.len());
+        for hex in hex_list.iter(){
+            let id = self.board[hex];
+            self.remove_piece(id);
+            consumed.push((id, *hex));
+        }
+        let piece = Some(self.insert_piece(category.kind(), team, base));
+        self.events.push(GameEvent::Combined { team, base, category, consumed, piece });
+        Ok(())
+    }
+
+    //from 칸에 team 의 기물이 있어야 함
+    fn own_piece_at(&self, team: Team, from: Hex) -> Result<&Piece, ActionError>{
+        let piece = self.piece_at(from).ok_or(ActionError::NoPiece(from))?;
+        if piece.team != team{
+            return Err(ActionError::NotOwner(from));
+        }
+        Ok(piece)
+    }
+
+    fn launch(&mut self, team: Team, from: Hex, direction: Hex) -> Result<(), ActionError>{
+        if Hex::ZERO.distance_to(direction) != 1{
+            return Err(ActionError::InvalidDirection(direction));
+        }
+        let piece = self.own_piece_at(team, from)?;
+        if piece.kind != PieceKind::Bishop{
+            return Err(ActionError::NotLaunchable(from));
+        }
+        let id = piece.id;
+        let piece = self.remove_piece(id).unwrap();
+        self.rockets.insert(id, Rocket{
+            id,
+            team: piece.team,
+            pos: from,
+            direction,
+            progress: 0.,
+            speed: self.rules.rocket_start_speed
+        });
+        self.events.push(GameEvent::Launched { from, direction });
+        Ok(())
+    }
+
+    fn throw(&mut self, team: Team, from: Hex, target: Hex) -> Result<(), ActionError>{
+        let piece = self.own_piece_at(team, from)?;
+        if piece.kind != PieceKind::Knight{
+            return Err(ActionError::NotLaunchable(from));
+        }
+        if piece.cooldown > 0.{
+            return Err(ActionError::NotReady(from));
+        }
+        if !self.throw_targets(from).contains(&target){
+            return Err(ActionError::OutOfRange(target));
+        }
+        let knight = piece.id;
+        self.pieces.get_mut(&knight).unwrap().cooldown = self.rules.knight_reload_time;
+
+        let id = self.next_id();
+        self.grenades.insert(id, Grenade{
+            id,
+            knight,
+            team,
+            from,
+            target,
+            elapsed: 0.,
+            flight_time: self.rules.grenade_flight_time
+        });
+        self.events.push(GameEvent::Thrown { id, from, target });
+        Ok(())
+    }
+
+    fn fire(&mut self, team: Team, from: Hex, direction: Hex) -> Result<(), ActionError>{
+        if Hex::ZERO.distance_to(direction) != 1{
+            return Err(ActionError::InvalidDirection(direction));
+        }
+        let piece = self.own_piece_at(team, from)?;
+        if piece.kind != PieceKind::Queen{
+            return Err(ActionError::NotLaunchable(from));
+        }
+        if piece.cooldown > 0.{
+            return Err(ActionError::NotReady(from));
+        }
+        let queen = piece.id;
+        self.pieces.get_mut(&queen).unwrap().cooldown = self.rules.queen_reload_time;
+
+        let id = self.next_id();
+        self.beams.insert(id, Beam{
+            id,
+            queen,
+            team,
+            pos: from,
+            direction,
+            progress: 0.
+        });
+        self.events.push(GameEvent::Fired { id, from });
+        Ok(())
+    }
+
+    fn tick_cooldowns(&mut self, delta: f32){
+        for piece in self.pieces.values_mut(){
+            piece.cooldown = (piece.cooldown - delta).max(0.);
+        }
+    }
+
+    fn tick_pawns(&mut self, delta: f32){
+        //팀 폰 수가 상한에 닿았거나 갇힌 폰은 타이머를 멈추고, 다시 풀리면 멈춘 곳부터 이어감
+        let mut counts: HashMap<Team, usize> = HashMap::new();
+        for piece in self.pieces.values().filter(|p| p.kind == PieceKind::Pawn){
+            *counts.entry(piece.team).or_default() += 1;
+        }
+        let paused: HashSet<PieceId> = self.pieces.values()
+            .filter(|p| p.kind == PieceKind::Pawn)
+            .filter(|p| counts[&p.team] >= self.rules.pawn_cap || self.is_boxed_in(p.pos))
+            .map(|p| p.id)
+            .collect();
+
+        let mut ready = Vec::new();
+        for piece in self.pieces.values_mut(){
+            if piece.kind != PieceKind::Pawn || paused.contains(&piece.id){
+                continue;
+            }
+            piece.spawn_elapsed += delta;
+            if piece.spawn_elapsed >= piece.spawn_time{
+                piece.spawn_elapsed -= piece.spawn_time;
+                ready.push((piece.id, piece.team, piece.pos));
+            }
+        }
+
+        for (parent, team, pos) in ready{
+            //같은 틱에 여러 폰이 증식해도 상한을 넘지 않음
+            let count = counts.entry(team).or_default();
+            if *count >= self.rules.pawn_cap{
+                continue;
+            }
+            let spawn_list: Vec<Hex> = self.neighbours(pos).into_iter()
+                .filter(|hex| self.is_clear(*hex))
+                .collect();
+            if spawn_list.is_empty(){
+                continue;
+            }
+            let hex_pos = spawn_list[self.rng.gen_index(spawn_list.len())];
+            *count += 1;
+            let id = self.insert_piece(PieceKind::Pawn, team, hex_pos);
+            self.events.push(GameEvent::PieceSpawned {
+                id,
+                kind: PieceKind::Pawn,
+                team,
+                pos: hex_pos,
+                parent: Some(parent)
+            });
+        }
+    }
+
+    fn tick_rockets(&mut self, delta: f32){
+        let ids: Vec<PieceId> = self.rockets.keys().copied().collect();
+        for id in ids{
+            let mut rocket = self.rockets[&id].clone();
+            if let Some(rook) = self.shield_at(rocket.pos, rocket.team){
+                self.rockets.remove(&id);
+                self.events.push(GameEvent::RocketBlocked { id, pos: rocket.pos, rook });
+                continue;
+            }
+            //적 기물이 있는 칸에 들어가면 폭발하면서 그 기물에 피해를 줌
+            let hit = self.piece_at(rocket.pos)
+                .filter(|p| p.team != rocket.team)
+                .map(|p| p.id);
+            if let Some(hit) = hit{
+                self.rockets.remove(&id);
+                self.events.push(GameEvent::RocketExploded { id, pos: rocket.pos });
+                self.damage(DamageEvent { source: id, target: hit, amount: self.rules.rocket_damage });
+                continue;
+            }
+
+            rocket.progress += delta * rocket.speed;
+            rocket.speed += delta * self.rules.rocket_acceleration;
+            let target_pos = rocket.pos + rocket.direction;
+            //보드 끝이나 바위 앞에서 폭발
+            if !self.is_passable(target_pos){
+                self.rockets.remove(&id);
+                self.events.push(GameEvent::RocketExploded { id, pos: rocket.pos });
+                continue;
+            }
+            if rocket.progress > 1.{
+                rocket.pos = target_pos;
+                rocket.progress = 0.;
+            }
+            self.rockets.insert(id, rocket);
+        }
+    }
+
+    fn tick_grenades(&mut self, delta: f32){
+        let mut landed = Vec::new();
+        for grenade in self.grenades.values_mut(){
+            grenade.elapsed += delta;
+            if grenade.elapsed >= grenade.flight_time{
+                landed.push(grenade.id);
+            }
+        }
+        for id in landed{
+            let grenade = self.grenades.remove(&id).unwrap();
+            self.events.push(GameEvent::GrenadeExploded { id, pos: grenade.target });
+            //착탄 지점과 그 주변 링에 있는 적 기물에 피해
+            let hits: Vec<PieceId> = self.spiral(grenade.target, self.rules.grenade_radius).into_iter()
+                .filter_map(|hex| self.piece_at(hex))
+                .filter(|p| p.team != grenade.team)
+                .map(|p| p.id)
+                .collect();
+            for hit in hits{
+                self.damage(DamageEvent { source: grenade.knight, target: hit, amount: self.rules.grenade_damage });
+            }
+        }
+    }
+
+    fn tick_beams(&mut self, delta: f32){
+        let ids: Vec<PieceId> = self.beams.keys().copied().collect();
+        for id in ids{
+            let mut beam = self.beams[&id].clone();
+            beam.progress += delta * self.rules.beam_speed;
+            let mut ended = false;
+            while beam.progress >= 1.{
+                let target_pos = beam.pos + beam.direction;
+                if !self.is_passable(target_pos){
+                    self.events.push(GameEvent::BeamEnded { id, pos: beam.pos });
+                    ended = true;
+                    break;
+                }
+                beam.pos = target_pos;
+                beam.progress -= 1.;
+                if let Some(rook) = self.shield_at(beam.pos, beam.team){
+                    self.events.push(GameEvent::BeamBlocked { id, pos: beam.pos, rook });
+                    ended = true;
+                    break;
+                }
+                //적을 관통하면서 지나가는 칸의 적 기물에 모두 피해
+                let hit = self.piece_at(beam.pos)
+                    .filter(|p| p.team != beam.team)
+                    .map(|p| p.id);
+                if let Some(hit) = hit{
+                    self.damage(DamageEvent { source: beam.queen, target: hit, amount: self.rules.beam_damage });
+                }
+            }
+            if ended{
+                self.beams.remove(&id);
+            }else{
+                self.beams.insert(id, beam);
+            }
+        }
+    }
+}
+
+impl HexBoard for GameState{
+    fn contains(&self, hex: Hex) -> bool{
+        self.tiles.contains(&hex)
+    }
+
+    fn is_blocked(&self, hex: Hex) -> bool{
+        self.blocked.contains(&hex)
+    }
+
+    fn is_occupied(&self, hex: Hex) -> bool{
+        self.board.contains_key(&hex)
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    //반지름 5의 빈 보드, 하위 모듈의 테스트도 같이 씀
+    pub(super) fn board() -> GameState{
+        GameState::new(hexx::shapes::hexagon(Hex::ZERO, 5))
+    }
+
+    impl GameState{
+        pub(crate) fn tiles(&self) -> &HashSet<Hex>{
+            &self.tiles
+        }
+
+        pub(crate) fn team_pawns(&self, team: Team) -> HashSet<Hex>{
+            self.pieces.values()
+                .filter(|p| p.kind == PieceKind::Pawn && p.team == team)
+                .map(|p| p.pos)
+                .collect()
+        }
+    }
+
+    #[test]
+    fn pawn_spawns_into_free_neighbour(){
+        let mut state = board();
+        let id = state.place(PieceKind::Pawn, Team::BLUE, Hex::ZERO).unwrap();
+        state.drain_events();
+        state.tick(PAWN_SPAWN_TIME_MAX);
+
+        let events = state.drain_events();
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            GameEvent::PieceSpawned { pos, parent, team, .. } => {
+                assert_eq!(*parent, Some(id));
+                assert_eq!(*team, Team::BLUE);
+                assert_eq!(Hex::ZERO.distance_to(*pos), 1);
+            },
+            ev => panic!("unexpected event {:?}", ev)
+        }
+        assert_eq!(state.team_pawns(Team::BLUE).len(), 2);
+    }
+
+    #[test]
+    fn bishop_combination_and_launch(){
+        let mut state = board();
+        let tiles: HashSet<Hex> = [Hex::new(0, 0), Hex::new(1, 0), Hex::new(2, 0)].iter().copied().collect();
+        for hex in tiles.iter(){
+            state.place(PieceKind::Pawn, Team::BLUE, *hex);
+        }
+        state.apply(GameAction::Combine { team: Team::BLUE, base: Hex::ZERO, tiles, category: None }).unwrap();
+        let bishop = state.piece_at(Hex::ZERO).unwrap().id;
+        assert_eq!(state.piece(bishop).unwrap().kind, PieceKind::Bishop);
+        assert!(state.team_pawns(Team::BLUE).is_empty());
+
+        state.apply(GameAction::Launch { team: Team::BLUE, from: Hex::ZERO, direction: Hex::new(-1, 0) }).unwrap();
+        assert!(state.piece_at(Hex::ZERO).is_none());
+        for _ in 0..100{
+            state.tick(0.1);
+        }
+        assert!(state.rocket(bishop).is_none());
+    }
+
+    #[test]
+    fn rook_blocks_rocket_on_neighbour_tile(){
+        let mut state = board();
+        let bishop = state.place(PieceKind::Bishop, Team::BLUE, Hex::new(-4, 0)).unwrap();
+        let rook = state.place(PieceKind::Rook, Team::RED, Hex::new(2, -1)).unwrap();
+        state.apply(GameAction::Launch { team: Team::BLUE, from: Hex::new(-4, 0), direction: Hex::new(1, 0) }).unwrap();
+        state.drain_events();
+
+        let mut blocked = None;
+        for _ in 0..100{
+            state.tick(0.1);
+            for ev in state.drain_events(){
+                if let GameEvent::RocketBlocked { id, pos, rook } = ev{
+                    blocked = Some((id, pos, rook));
+                }
+            }
+        }
+        assert_eq!(blocked, Some((bishop, Hex::new(1, 0), rook)));
+        assert!(state.piece(rook).is_some());
+    }
+
+    #[test]
+    fn grenade_clears_enemy_ring(){
+        let mut state = board();
+        state.place(PieceKind::Knight, Team::BLUE, Hex::new(-2, 0));
+        let inside = state.place(PieceKind::Pawn, Team::RED, Hex::new(2, -1)).unwrap();
+        let outside = state.place(PieceKind::Pawn, Team::RED, Hex::new(4, 0)).unwrap();
+        let own = state.place(PieceKind::Pawn, Team::BLUE, Hex::new(1, 0)).unwrap();
+
+        assert_eq!(
+            state.apply(GameAction::Throw { team: Team::BLUE, from: Hex::new(-2, 0), target: Hex::new(3, 0) }),
+            Err(ActionError::OutOfRange(Hex::new(3, 0)))
+        );
+        state.apply(GameAction::Throw { team: Team::BLUE, from: Hex::new(-2, 0), target: Hex::new(2, 0) }).unwrap();
+        assert_eq!(
+            state.apply(GameAction::Throw { team: Team::BLUE, from: Hex::new(-2, 0), target: Hex::new(2, 0) }),
+            Err(ActionError::NotReady(Hex::new(-2, 0)))
+        );
+        state.tick(GRENADE_FLIGHT_TIME);
+
+        assert!(state.piece(inside).is_none());
+        assert!(state.piece(outside).is_some());
+        assert!(state.piece(own).is_some());
+    }
+
+    #[test]
+    fn beam_pierces_every_enemy_on_the_line(){
+        let mut state = board();
+        let queen = state.place(PieceKind::Queen, Team::BLUE, Hex::new(-5, 0)).unwrap();
+        let first = state.place(PieceKind::Pawn, Team::RED, Hex::new(-1, 0)).unwrap();
+        let second = state.place(PieceKind::Knight, Team::RED, Hex::new(3, 0)).unwrap();
+        let own = state.place(PieceKind::Pawn, Team::BLUE, Hex::new(1, 0)).unwrap();
+        state.apply(GameAction::Fire { team: Team::BLUE, from: Hex::new(-5, 0), direction: Hex::new(1, 0) }).unwrap();
+        state.drain_events();
+        for _ in 0..10{
+            state.tick(0.1);
+        }
+
+        assert!(state.piece(first).is_none());
+        assert!(state.piece(second).is_none());
+        assert!(state.piece(own).is_some());
+        assert!(state.piece(queen).is_some());
+        let ended = state.drain_events().iter()
+            .any(|ev| matches!(ev, GameEvent::BeamEnded { pos, .. } if *pos == Hex::new(5, 0)));
+        assert!(ended);
+    }
+
+    #[test]
+    fn capturing_the_king_ends_the_game(){
+        let mut state = board();
+        state.place(PieceKind::Knight, Team::BLUE, Hex::new(-2, 0));
+        state.place(PieceKind::King, Team::RED, Hex::new(2, 0));
+        state.place(PieceKind::Pawn, Team::BLUE, Hex::new(-4, 0));
+        state.apply(GameAction::Throw { team: Team::BLUE, from: Hex::new(-2, 0), target: Hex::new(2, 0) }).unwrap();
+        state.tick(GRENADE_FLIGHT_TIME);
+
+        assert_eq!(state.outcome(), Some(Outcome::Winner(Team::BLUE)));
+        let events = state.drain_events();
+        assert!(events.iter().any(|ev| matches!(ev, GameEvent::TeamEliminated { team: Team::RED })));
+        assert!(events.iter().any(|ev| matches!(ev, GameEvent::GameOver { outcome: Outcome::Winner(Team::BLUE) })));
+        state.tick(PAWN_SPAWN_TIME_MAX);
+        assert!(state.drain_events().is_empty());
+        assert_eq!(state.team_pawns(Team::BLUE).len(), 1);
+        assert_eq!(
+            state.apply(GameAction::Throw { team: Team::BLUE, from: Hex::new(-2, 0), target: Hex::new(1, 0) }),
+            Err(ActionError::GameOver)
+        );
+    }
+
+    #[test]
+    fn kings_lost_in_the_same_tick_draw(){
+        let mut state = board();
+        state.place(PieceKind::Knight, Team::BLUE, Hex::new(-1, 0));
+        state.place(PieceKind::King, Team::BLUE, Hex::new(-3, 0));
+        state.place(PieceKind::Knight, Team::RED, Hex::new(1, 0));
+        state.place(PieceKind::King, Team::RED, Hex::new(3, 0));
+        state.apply(GameAction::Throw { team: Team::BLUE, from: Hex::new(-1, 0), target: Hex::new(3, 0) }).unwrap();
+        state.apply(GameAction::Throw { team: Team::RED, from: Hex::new(1, 0), target: Hex::new(-3, 0) }).unwrap();
+        state.tick(GRENADE_FLIGHT_TIME);
+
+        assert!(state.alive_teams().is_empty());
+        assert_eq!(state.outcome(), Some(Outcome::Draw));
+        let overs = state.drain_events().iter()
+            .filter(|ev| matches!(ev, GameEvent::GameOver { .. }))
+            .count();
+        assert_eq!(overs, 1);
+    }
+
+    #[test]
+    fn combination_needs_own_pawns(){
+        let mut state = board();
+        let tiles: HashSet<Hex> = [Hex::new(0, 0), Hex::new(1, 0), Hex::new(2, 0)].iter().copied().collect();
+        for hex in tiles.iter(){
+            state.place(PieceKind::Pawn, Team::RED, *hex);
+        }
+        let result = state.apply(GameAction::Combine { team: Team::BLUE, base: Hex::ZERO, tiles, category: None });
+        assert_eq!(result, Err(ActionError::NoCombination));
+        assert_eq!(state.team_pawns(Team::RED).len(), 3);
+    }
+
+    #[test]
+    fn free_for_all_ends_with_the_last_king(){
+        let mut state = board();
+        let green = Team(2);
+        state.place(PieceKind::Knight, Team::BLUE, Hex::new(-2, 0));
+        state.place(PieceKind::King, Team::BLUE, Hex::new(-4, 0));
+        state.place(PieceKind::King, Team::RED, Hex::new(2, 0));
+        let red_pawn = state.place(PieceKind::Pawn, Team::RED, Hex::new(4, -4)).unwrap();
+        state.place(PieceKind::King, green, Hex::new(-1, 3));
+
+        state.apply(GameAction::Throw { team: Team::BLUE, from: Hex::new(-2, 0), target: Hex::new(2, 0) }).unwrap();
+        state.tick(GRENADE_FLIGHT_TIME);
+        assert!(state.piece(red_pawn).is_none());
+        assert_eq!(state.alive_teams(), vec![Team::BLUE, green]);
+        assert_eq!(state.outcome(), None);
+
+        state.tick(KNIGHT_RELOAD_TIME);
+        state.apply(GameAction::Throw { team: Team::BLUE, from: Hex::new(-2, 0), target: Hex::new(-1, 3) }).unwrap();
+        state.tick(GRENADE_FLIGHT_TIME);
+        assert_eq!(state.outcome(), Some(Outcome::Winner(Team::BLUE)));
+    }
+
+    #[test]
+    fn rocket_destroys_the_enemy_it_hits(){
+        let mut state = board();
+        state.place(PieceKind::Bishop, Team::BLUE, Hex::new(-4, 0));
+        let own = state.place(PieceKind::Knight, Team::BLUE, Hex::new(-2, 0)).unwrap();
+        let enemy = state.place(PieceKind::Knight, Team::RED, Hex::new(1, 0)).unwrap();
+        let behind = state.place(PieceKind::Knight, Team::RED, Hex::new(2, 0)).unwrap();
+        state.apply(GameAction::Launch { team: Team::BLUE, from: Hex::new(-4, 0), direction: Hex::new(1, 0) }).unwrap();
+        state.drain_events();
+
+        let mut destroyed = Vec::new();
+        for _ in 0..100{
+            state.tick(0.1);
+            for ev in state.drain_events(){
+                if let GameEvent::PieceDestroyed { id, pos, .. } = ev{
+                    destroyed.push((id, pos));
+                }
+            }
+        }
+        assert_eq!(destroyed, vec![(enemy, Hex::new(1, 0))]);
+        assert!(state.piece(own).is_some());
+        assert!(state.piece(behind).is_some());
+        assert!(state.is_clear(Hex::new(1, 0)));
+    }
+
+    #[test]
+    fn rook_survives_a_grenade_with_reduced_hp(){
+        let mut state = board();
+        let knight = state.place(PieceKind::Knight, Team::BLUE, Hex::new(-2, 0)).unwrap();
+        let rook = state.place(PieceKind::Rook, Team::RED, Hex::new(2, 0)).unwrap();
+        state.apply(GameAction::Throw { team: Team::BLUE, from: Hex::new(-2, 0), target: Hex::new(2, 0) }).unwrap();
+        state.drain_events();
+        state.tick(GRENADE_FLIGHT_TIME);
+
+        let damaged: Vec<(DamageEvent, u32)> = state.drain_events().into_iter()
+            .filter_map(|ev| match ev {
+                GameEvent::Damaged { damage, hp } => Some((damage, hp)),
+                _ => None
+            })
+            .collect();
+        let expected = DamageEvent { source: knight, target: rook, amount: GRENADE_DAMAGE };
+        assert_eq!(damaged, vec![(expected, PieceKind::Rook.max_hp() - GRENADE_DAMAGE)]);
+        assert_eq!(state.piece(rook).unwrap().hp, PieceKind::Rook.max_hp() - GRENADE_DAMAGE);
+
+        state.tick(KNIGHT_RELOAD_TIME);
+        state.apply(GameAction::Throw { team: Team::BLUE, from: Hex::new(-2, 0), target: Hex::new(2, 0) }).unwrap();
+        state.tick(GRENADE_FLIGHT_TIME);
+        assert!(state.piece(rook).is_none());
+    }
+
+    #[test]
+    fn same_seed_and_actions_replay_the_same_game(){
+        let run = |seed: u64| -> Vec<String>{
+            let mut state = GameState::with_seed(hexx::shapes::hexagon(Hex::ZERO, 5), seed);
+            state.place(PieceKind::Pawn, Team::BLUE, Hex::new(-3, 0));
+            state.place(PieceKind::Pawn, Team::RED, Hex::new(3, 0));
+            state.place(PieceKind::Knight, Team::BLUE, Hex::new(-1, 0));
+            let mut log = Vec::new();
+            for step in 0..200{
+                if step == 50{
+                    state.apply(GameAction::Throw { team: Team::BLUE, from: Hex::new(-1, 0), target: Hex::new(3, 0) }).ok();
+                }
+                state.tick(1. / 20.);
+                log.extend(state.drain_events().iter().map(|ev| format!("{:?}", ev)));
+            }
+            log
+        };
+        assert_eq!(run(7), run(7));
+        assert_eq!(GameState::with_seed(hexx::shapes::hexagon(Hex::ZERO, 5), 7).seed(), 7);
+    }
+
+    #[test]
+    fn knight_range_and_damage_come_from_the_rules(){
+        let mut state = board();
+        state.set_rules(GameRules { knight_range: (1, 2), grenade_damage: 5, ..Default::default() });
+        state.place(PieceKind::Knight, Team::BLUE, Hex::ZERO);
+        let rook = state.place(PieceKind::Rook, Team::RED, Hex::new(1, 0)).unwrap();
+        assert!(state.throw_targets(Hex::ZERO).contains(&Hex::new(1, 0)));
+        assert!(!state.throw_targets(Hex::ZERO).contains(&Hex::new(3, 0)));
+        state.apply(GameAction::Throw { team: Team::BLUE, from: Hex::ZERO, target: Hex::new(1, 0) }).unwrap();
+        state.tick(GRENADE_FLIGHT_TIME);
+        assert!(state.piece(rook).is_none());
+    }
+
+    #[test]
+    fn fixed_spawn_interval_grows_on_time(){
+        let mut state = board();
+        state.set_rules(GameRules { pawn_spawn_time: (3., 3.), ..Default::default() });
+        state.place(PieceKind::Pawn, Team::BLUE, Hex::ZERO);
+        state.tick(2.5);
+        assert_eq!(state.pawn_count(Team::BLUE), 1);
+        state.tick(0.5);
+        assert_eq!(state.pawn_count(Team::BLUE), 2);
+    }
+
+    #[test]
+    fn pawn_growth_stops_at_the_cap(){
+        let mut state = board();
+        state.set_rules(GameRules { pawn_cap: 3, ..Default::default() });
+        state.place(PieceKind::Pawn, Team::BLUE, Hex::ZERO);
+        for _ in 0..20{
+            state.tick(PAWN_SPAWN_TIME_MAX);
+        }
+        assert_eq!(state.pawn_count(Team::BLUE), 3);
+    }
+
+    #[test]
+    fn boxed_in_pawn_waits_until_a_neighbour_frees_up(){
+        let mut state = board();
+        let pawn = state.place(PieceKind::Pawn, Team::RED, Hex::ZERO).unwrap();
+        for neighbor in Hex::ZERO.all_neighbors().iter(){
+            state.place(PieceKind::Knight, Team::BLUE, *neighbor);
+        }
+        state.place(PieceKind::Knight, Team::RED, Hex::new(4, 0));
+        state.tick(PAWN_SPAWN_TIME_MAX * 3.);
+        assert!(state.is_boxed_in(Hex::ZERO));
+        assert_eq!(state.piece(pawn).unwrap().spawn_elapsed, 0.);
+        assert_eq!(state.pawn_count(Team::RED), 1);
+
+        state.apply(GameAction::Throw { team: Team::RED, from: Hex::new(4, 0), target: Hex::new(1, 0) }).unwrap();
+        state.tick(GRENADE_FLIGHT_TIME);
+        assert!(!state.is_boxed_in(Hex::ZERO));
+        state.tick(PAWN_SPAWN_TIME_MAX);
+        assert_eq!(state.pawn_count(Team::RED), 2);
+    }
+
+    #[test]
+    fn rocks_stop_rockets_and_pawn_growth(){
+        let mut state = board();
+        state.set_blocked([Hex::new(0, 0), Hex::new(9, 0)]);
+        assert!(state.is_blocked(Hex::ZERO));
+        assert!(!state.is_blocked(Hex::new(9, 0)));
+        assert_eq!(state.place(PieceKind::Pawn, Team::BLUE, Hex::ZERO), None);
+
+        let bishop = state.place(PieceKind::Bishop, Team::BLUE, Hex::new(-4, 0)).unwrap();
+        let behind = state.place(PieceKind::Knight, Team::RED, Hex::new(2, 0)).unwrap();
+        state.apply(GameAction::Launch { team: Team::BLUE, from: Hex::new(-4, 0), direction: Hex::new(1, 0) }).unwrap();
+        let mut exploded = None;
+        for _ in 0..100{
+            state.tick(0.1);
+            for ev in state.drain_events(){
+                if let GameEvent::RocketExploded { id, pos } = ev{
+                    exploded = Some((id, pos));
+                }
+            }
+        }
+        assert_eq!(exploded, Some((bishop, Hex::new(-1, 0))));
+        assert!(state.piece(behind).is_some());
+
+        let pawn = state.place(PieceKind::Pawn, Team::BLUE, Hex::new(1, -1)).unwrap();
+        for _ in 0..10{
+            state.tick(PAWN_SPAWN_TIME_MAX);
+        }
+        assert!(state.piece(pawn).is_some());
+        assert!(state.piece_at(Hex::ZERO).is_none());
+    }
+
+    #[test]
+    fn actions_survive_serialization_and_check_the_owner(){
+        let mut state = board();
+        state.place(PieceKind::Knight, Team::RED, Hex::new(-2, 0));
+        let action = GameAction::Throw { team: Team::BLUE, from: Hex::new(-2, 0), target: Hex::new(2, 0) };
+        let text = serde_json::to_string(&action).unwrap();
+        assert_eq!(serde_json::from_str::<GameAction>(&text).unwrap(), action);
+        //다른 팀의 기사는 던질 수 없음
+        assert_eq!(state.apply(action), Err(ActionError::NotOwner(Hex::new(-2, 0))));
+        state.apply(GameAction::Throw { team: Team::RED, from: Hex::new(-2, 0), target: Hex::new(2, 0) }).unwrap();
+    }
+}
@@ -0,0 +1,84 @@
+use hexx::Hex;
+use serde::{Deserialize, Serialize};
+
+use super::Team;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PieceId(pub u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PieceKind{
+    Pawn,
+    Bishop,
+    Rook,
+    Knight,
+    Queen,
+    King
+}
+
+impl PieceKind{
+    //기물별 최대 체력
+    pub fn max_hp(&self) -> u32{
+        match self {
+            PieceKind::Pawn => 1,
+            PieceKind::Bishop => 1,
+            PieceKind::Rook => 3,
+            PieceKind::Knight => 2,
+            PieceKind::Queen => 2,
+            PieceKind::King => 2
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Piece{
+    pub id: PieceId,
+    pub kind: PieceKind,
+    pub team: Team,
+    pub pos: Hex,
+    pub hp: u32,
+    pub spawn_time: f32, //폰만 사용하는 증식 주기
+    pub spawn_elapsed: f32,
+    pub cooldown: f32 //다시 공격할 수 있을 때까지 남은 시간
+}
+
+//발사된 비숍, 보드에서는 빠지고 한 칸씩 날아감
+#[derive(Debug, Clone)]
+pub struct Rocket{
+    pub id: PieceId,
+    pub team: Team,
+    pub pos: Hex,
+    pub direction: Hex,
+    pub progress: f32, //다음 칸까지 진행도 (0 ~ 1)
+    pub speed: f32
+}
+
+//나이트가 던진 유탄, 포물선을 그리며 다른 기물 위로 날아감
+#[derive(Debug, Clone)]
+pub struct Grenade{
+    pub id: PieceId,
+    pub knight: PieceId,
+    pub team: Team,
+    pub from: Hex,
+    pub target: Hex,
+    pub elapsed: f32,
+    pub flight_time: f32
+}
+
+impl Grenade{
+    //비행 진행도 (0 ~ 1)
+    pub fn progress(&self) -> f32{
+        (self.elapsed / self.flight_time).min(1.)
+    }
+}
+
+//퀸의 관통탄, 적을 만나도 멈추지 않고 보드 끝까지 직진함
+#[derive(Debug, Clone)]
+pub struct Beam{
+    pub id: PieceId,
+    pub queen: PieceId,
+    pub team: Team,
+    pub pos: Hex,
+    pub direction: Hex,
+    pub progress: f32
+}
@@ -0,0 +1,173 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{GameAction, GameRules, MapFile, Recipe, RulesError, MapError, RecipeError};
+
+//지금 읽을 수 있는 리플레이 파일 버전, 형식이 바뀌면 올림
+pub const REPLAY_VERSION: u32 = 2;
+
+//기물을 놓은 뒤 tick 번째 스텝 전에 적용된 행동
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimedAction{
+    pub tick: u64,
+    pub action: GameAction
+}
+
+//판을 시작할 때의 규칙, 보드, 조합 규칙과 시드, 적용된 행동으로 같은 판을 다시 만듦 (replays/*.replay.json)
+//규칙 파일이 나중에 바뀌어도 기록한 값으로 재생함
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay{
+    pub version: u32,
+    pub seed: u64,
+    pub rules: GameRules,
+    pub board: MapFile,
+    pub recipes: Vec<Recipe>,
+    pub timestep: f32, //한 스텝의 길이 (초)
+    pub length: u64, //기록한 스텝 수
+    pub actions: Vec<TimedAction>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplayError{
+    UnsupportedVersion(u32),
+    BadTimestep(f32),
+    Rules(RulesError),
+    Board(MapError),
+    Recipe(RecipeError),
+    OutOfOrder(usize), //앞의 행동보다 이른 스텝에 있음
+    PastTheEnd(usize) //기록 길이보다 뒤에 있음
+}
+
+impl fmt::Display for ReplayError{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::UnsupportedVersion(version) =>
+                write!(f, "unsupported replay version {} (expected {})", version, REPLAY_VERSION),
+            ReplayError::BadTimestep(timestep) => write!(f, "timestep {} is not a positive number", timestep),
+            ReplayError::Rules(err) => write!(f, "recorded rules: {}", err),
+            ReplayError::Board(err) => write!(f, "recorded board: {}", err),
+            ReplayError::Recipe(err) => write!(f, "recorded recipes: {}", err),
+            ReplayError::OutOfOrder(index) => write!(f, "action {} is earlier than the one before it", index),
+            ReplayError::PastTheEnd(index) => write!(f, "action {} is after the end of the recording", index)
+        }
+    }
+}
+
+impl std::error::Error for ReplayError{}
+
+impl Replay{
+    pub fn new(seed: u64, rules: GameRules, board: MapFile, recipes: Vec<Recipe>, timestep: f32) -> Self{
+        Self { version: REPLAY_VERSION, seed, rules, board, recipes, timestep, length: 0, actions: Vec::new() }
+    }
+
+    pub fn record(&mut self, tick: u64, action: GameAction){
+        self.length = self.length.max(tick);
+        self.actions.push(TimedAction { tick, action });
+    }
+
+    //cursor 번째부터 tick 번째 스텝 전까지 적용해야 하는 행동들
+    pub fn due(&self, cursor: usize, tick: u64) -> &[TimedAction]{
+        let rest = self.actions.get(cursor..).unwrap_or_default();
+        let count = rest.iter().take_while(|timed| timed.tick <= tick).count();
+        &rest[..count]
+    }
+
+    pub fn seconds(&self, ticks: u64) -> f32{
+        ticks as f32 * self.timestep
+    }
+
+    //처음 발견한 문제 하나를 알려줌
+    pub fn validate(&self) -> Result<(), ReplayError>{
+        if self.version != REPLAY_VERSION{
+            return Err(ReplayError::UnsupportedVersion(self.version));
+        }
+        if !(self.timestep > 0.){
+            return Err(ReplayError::BadTimestep(self.timestep));
+        }
+        self.rules.validate().map_err(ReplayError::Rules)?;
+        self.board.validate().map_err(ReplayError::Board)?;
+        self.board.check_players(self.rules.players).map_err(ReplayError::Board)?;
+        for recipe in self.recipes.iter(){
+            recipe.validate().map_err(ReplayError::Recipe)?;
+        }
+        let mut last = 0;
+        for (index, timed) in self.actions.iter().enumerate(){
+            if timed.tick < last{
+                return Err(ReplayError::OutOfOrder(index));
+            }
+            if timed.tick > self.length{
+                return Err(ReplayError::PastTheEnd(index));
+            }
+            last = timed.tick;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use hexx::Hex;
+
+    use super::*;
+    use super::super::{GameState, PieceKind, Team, BoardShape, default_recipes, MAX_TEAMS};
+
+    #[test]
+    fn recorded_replay_plays_back_the_same_game(){
+        //리플레이에 들어 있는 보드, 규칙, 조합 규칙만으로 판을 다시 만듦
+        let start = |replay: &Replay| -> GameState{
+            let tiles = replay.board.tiles.iter().map(|tile| Hex::new(tile.x, tile.y));
+            let mut state = GameState::with_seed(tiles, replay.seed);
+            state.set_rules(replay.rules.clone());
+            state.set_recipes(replay.recipes.clone());
+            state.place(PieceKind::Pawn, Team::BLUE, Hex::new(-3, 0));
+            state.place(PieceKind::Pawn, Team::RED, Hex::new(3, 0));
+            state.place(PieceKind::Knight, Team::BLUE, Hex::new(-1, 0));
+            state
+        };
+        let throw = GameAction::Throw { team: Team::BLUE, from: Hex::new(-1, 0), target: Hex::new(3, 0) };
+
+        let rules = GameRules { knight_reload_time: 2., ..Default::default() };
+        let board = MapFile::from_shape(&BoardShape::Hexagon { radius: 5 }, &[]);
+        let mut replay = Replay::new(11, rules, board, default_recipes(), 1. / 20.);
+        let mut state = start(&replay);
+        let mut recorded = Vec::new();
+        for tick in 0..200{
+            if tick == 40 || tick == 120{
+                state.apply(throw.clone()).unwrap();
+                replay.record(tick, throw.clone());
+            }
+            state.tick(replay.timestep);
+            recorded.extend(state.drain_events().iter().map(|ev| format!("{:?}", ev)));
+        }
+        replay.length = 200;
+
+        let replay: Replay = serde_json::from_str(&serde_json::to_string(&replay).unwrap()).unwrap();
+        assert_eq!(replay.validate(), Ok(()));
+        let mut state = start(&replay);
+        let mut played = Vec::new();
+        let mut cursor = 0;
+        for tick in 0..replay.length{
+            for timed in replay.due(cursor, tick){
+                state.apply(timed.action.clone()).unwrap();
+                cursor += 1;
+            }
+            state.tick(replay.timestep);
+            played.extend(state.drain_events().iter().map(|ev| format!("{:?}", ev)));
+        }
+        assert_eq!(cursor, 2);
+        assert_eq!(played, recorded);
+
+        let mut broken = replay.clone();
+        broken.actions.swap(0, 1);
+        assert_eq!(broken.validate(), Err(ReplayError::OutOfOrder(1)));
+
+        //기록된 규칙이나 보드가 잘못되면 재생하지 않음
+        let mut broken = replay.clone();
+        broken.rules.players = MAX_TEAMS + 1;
+        assert_eq!(broken.validate(), Err(ReplayError::Rules(RulesError::Players(MAX_TEAMS + 1))));
+        let mut broken = replay;
+        broken.board.tiles.clear();
+        assert_eq!(broken.validate(), Err(ReplayError::Board(MapError::Empty)));
+    }
+}
@@ -0,0 +1,35 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+//한 판의 모든 무작위 요소(증식 주기, 증식 위치)는 이 시드 하나에서 나옴
+//같은 시드와 같은 입력이면 항상 같은 게임이 됨
+//StdRng 는 rand 버전에 따라 알고리즘이 바뀔 수 있어서 리플레이가 깨지지 않도록 ChaCha8 로 고정함
+#[derive(Debug, Clone)]
+pub struct GameRng{
+    seed: u64,
+    rng: ChaCha8Rng
+}
+
+impl GameRng{
+    pub fn new(seed: u64) -> Self{
+        Self { seed, rng: ChaCha8Rng::seed_from_u64(seed) }
+    }
+
+    pub fn seed(&self) -> u64{
+        self.seed
+    }
+
+    pub fn gen_range(&mut self, range: std::ops::RangeInclusive<f32>) -> f32{
+        self.rng.gen_range(range)
+    }
+
+    pub fn gen_index(&mut self, len: usize) -> usize{
+        self.rng.gen_range(0..len)
+    }
+}
+
+impl Default for GameRng{
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
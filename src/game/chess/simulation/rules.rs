@@ -0,0 +1,150 @@
+use std::fmt;
+
+use hexx::Hex;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+//밸런스 실험용 게임 규칙, assets/game.rules.json 에서 읽어옴
+//파일에 없는 항목은 기본값(이 모듈 위의 상수들)을 씀
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameRules{
+    pub map: Option<String>, //assets 아래의 맵 파일 경로, 있으면 board와 obstacles 대신 씀
+    pub players: usize, //참가하는 팀 수 (2 ~ MAX_TEAMS), 맵에 시작 위치가 있으면 팀마다 하나씩 있어야 함
+    pub board: BoardShape,
+    pub hex_size: f32, //렌더링에서만 씀
+    pub obstacles: Vec<Hex>, //바위로 막힌 칸
+    pub pawn_spawn_time: (f32, f32), //증식 주기 (최소, 최대)
+    pub pawn_cap: usize,
+    pub rocket_start_speed: f32,
+    pub rocket_acceleration: f32,
+    pub rocket_damage: u32,
+    pub knight_range: (u32, u32), //유탄을 던질 수 있는 거리 (최소, 최대)
+    pub knight_reload_time: f32,
+    pub grenade_flight_time: f32,
+    pub grenade_radius: u32,
+    pub grenade_damage: u32,
+    pub queen_reload_time: f32,
+    pub beam_speed: f32,
+    pub beam_damage: u32
+}
+
+impl Default for GameRules{
+    fn default() -> Self {
+        Self {
+            map: None,
+            players: PLAYERS,
+            board: BoardShape::default(),
+            hex_size: 0.15,
+            obstacles: Vec::new(),
+            pawn_spawn_time: (PAWN_SPAWN_TIME_MIN, PAWN_SPAWN_TIME_MAX),
+            pawn_cap: PAWN_CAP,
+            rocket_start_speed: ROCKET_START_SPEED,
+            rocket_acceleration: ROCKET_ACCELERATION,
+            rocket_damage: ROCKET_DAMAGE,
+            knight_range: (KNIGHT_RANGE_MIN, KNIGHT_RANGE_MAX),
+            knight_reload_time: KNIGHT_RELOAD_TIME,
+            grenade_flight_time: GRENADE_FLIGHT_TIME,
+            grenade_radius: GRENADE_RADIUS,
+            grenade_damage: GRENADE_DAMAGE,
+            queen_reload_time: QUEEN_RELOAD_TIME,
+            beam_speed: BEAM_SPEED,
+            beam_damage: BEAM_DAMAGE
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RulesError{
+    Players(usize),
+    NotPositive(&'static str, f32), //0보다 커야 하는 항목
+    Negative(&'static str, f32), //0 이상이어야 하는 항목
+    SpawnTime(f32, f32) //증식 주기의 최소가 최대보다 큼
+}
+
+impl fmt::Display for RulesError{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RulesError::Players(players) => write!(f, "{} players is out of range (2 ~ {})", players, MAX_TEAMS),
+            RulesError::NotPositive(field, value) => write!(f, "{} must be greater than 0 (got {})", field, value),
+            RulesError::Negative(field, value) => write!(f, "{} must not be negative (got {})", field, value),
+            RulesError::SpawnTime(min, max) => write!(f, "pawn_spawn_time minimum {} is greater than the maximum {}", min, max)
+        }
+    }
+}
+
+impl std::error::Error for RulesError{}
+
+impl GameRules{
+    pub fn validate(&self) -> Result<(), RulesError>{
+        if self.players < 2 || self.players > MAX_TEAMS{
+            return Err(RulesError::Players(self.players));
+        }
+        let positive = [
+            ("hex_size", self.hex_size),
+            ("pawn_spawn_time", self.pawn_spawn_time.0),
+            ("rocket_start_speed", self.rocket_start_speed),
+            ("grenade_flight_time", self.grenade_flight_time),
+            ("beam_speed", self.beam_speed)
+        ];
+        //NaN 도 여기서 걸러짐
+        if let Some((field, value)) = positive.iter().copied().find(|(_, value)| !(*value > 0.)){
+            return Err(RulesError::NotPositive(field, value));
+        }
+        let non_negative = [
+            ("rocket_acceleration", self.rocket_acceleration),
+            ("knight_reload_time", self.knight_reload_time),
+            ("queen_reload_time", self.queen_reload_time)
+        ];
+        if let Some((field, value)) = non_negative.iter().copied().find(|(_, value)| !(*value >= 0.)){
+            return Err(RulesError::Negative(field, value));
+        }
+        let (spawn_min, spawn_max) = self.pawn_spawn_time;
+        if spawn_min > spawn_max{
+            return Err(RulesError::SpawnTime(spawn_min, spawn_max));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn rules_file_overrides_only_listed_values(){
+        let rules: GameRules = serde_json::from_str(r#"{ "knight_range": [1, 2], "grenade_damage": 5 }"#).unwrap();
+        assert_eq!(rules, GameRules { knight_range: (1, 2), grenade_damage: 5, ..Default::default() });
+        //항목이 하나도 없으면 기본 규칙
+        let empty: GameRules = serde_json::from_str("{}").unwrap();
+        assert_eq!(empty, GameRules::default());
+    }
+
+    #[test]
+    fn bad_sizes_and_spawn_times_name_the_field(){
+        let rules = GameRules { pawn_spawn_time: (3., 2.), ..Default::default() };
+        assert_eq!(rules.validate(), Err(RulesError::SpawnTime(3., 2.)));
+        let rules = GameRules { hex_size: 0., ..Default::default() };
+        assert_eq!(rules.validate(), Err(RulesError::NotPositive("hex_size", 0.)));
+        let rules = GameRules { beam_speed: -1., ..Default::default() };
+        assert_eq!(rules.validate(), Err(RulesError::NotPositive("beam_speed", -1.)));
+        let rules = GameRules { grenade_flight_time: f32::NAN, ..Default::default() };
+        assert!(matches!(rules.validate(), Err(RulesError::NotPositive("grenade_flight_time", _))));
+        let rules = GameRules { rocket_acceleration: -0.5, ..Default::default() };
+        assert_eq!(rules.validate(), Err(RulesError::Negative("rocket_acceleration", -0.5)));
+        assert!(rules.validate().unwrap_err().to_string().starts_with("rocket_acceleration"));
+        //고정 주기는 허용
+        let rules = GameRules { pawn_spawn_time: (3., 3.), ..Default::default() };
+        assert_eq!(rules.validate(), Ok(()));
+    }
+
+    #[test]
+    fn player_count_must_fit_the_team_limit(){
+        assert_eq!(GameRules::default().validate(), Ok(()));
+        let rules = GameRules { players: MAX_TEAMS + 1, ..Default::default() };
+        assert_eq!(rules.validate(), Err(RulesError::Players(MAX_TEAMS + 1)));
+        let rules = GameRules { players: 1, ..Default::default() };
+        assert_eq!(rules.validate(), Err(RulesError::Players(1)));
+    }
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+pub const MAX_TEAMS: usize = 6;
+
+//팀 번호, 색이나 시작 위치는 이 번호로 정해짐
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Team(pub u8);
+
+impl Team{
+    pub const BLUE: Team = Team(0);
+    pub const RED: Team = Team(1);
+
+    pub fn all(team_count: usize) -> impl Iterator<Item = Team>{
+        (0..team_count.min(MAX_TEAMS) as u8).map(Team)
+    }
+}
+
+//게임이 끝난 결과
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome{
+    Winner(Team),
+    Draw //마지막 왕들이 동시에 잡힘
+}
@@ -1,13 +1,22 @@
 use std::{f32::consts::PI, collections::{HashMap, HashSet}};
 
-use bevy::{prelude::*, render::render_resource::PrimitiveTopology, input::mouse::MouseButtonInput};
-use bevy_mod_picking::{PickableBundle, prelude::{Pointer, On, Listener, Over, Out, RaycastPickTarget, Down}};
+use bevy::{prelude::*, render::render_resource::PrimitiveTopology};
+use bevy_mod_picking::{PickableBundle, prelude::{Pointer, On, Listener, Over, Out, RaycastPickTarget, Down, PointerButton}};
 use hexx::*;
+
+use crate::game::chess::{interaction::InteractionMode, rules::RulesRes, map_asset::BoardRes, simulation::{Team, TileKind, PieceKind, HexBoard, RayStep}};
 // use wasm_bindgen::JsValue;
 // use web_sys::console;
 
-pub const HEX_SIZE:f32 = 0.15;
-pub const MAP_RADIUS: u32 = 10;
+//팀 번호 순서대로 쓰는 색
+pub const TEAM_COLORS: [Color; 6] = [
+    Color::rgb(0.18, 0.44, 0.725),
+    Color::rgb(0.858, 0.266, 0.333),
+    Color::rgb(0.305, 0.654, 0.301),
+    Color::rgb(0.941, 0.741, 0.188),
+    Color::rgb(0.549, 0.333, 0.756),
+    Color::rgb(0.929, 0.541, 0.192)
+];
 fn hexagonal_column(huneycomb_size: f32) -> Mesh{
     let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
     let mut l_transform = Transform::from_translation(Vec3::new(0., 0., huneycomb_size));
@@ -69,24 +78,182 @@ fn hexagonal_column(huneycomb_size: f32) -> Mesh{
     mesh
 }
 
-#[derive(Debug, Resource)]
+//칸 위에 놓인 기물, 타일 색이나 기물 엔티티가 필요하면 모두 이것만 봄
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Occupant{
+    pub team: Team,
+    pub kind: PieceKind,
+    pub entity: Entity //타일의 자식으로 붙은 기물 모델
+}
+
+//조준 중인 칸을 눌렀을 때 고른 것
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AimTarget{
+    Direction(Hex), //6방향 중 하나
+    Tile(Hex) //목표 칸
+}
+
+#[derive(Debug, Resource, Default)]
 pub struct Map {
     pub entities: HashMap<Hex, Entity>,
     pub entities_forentity: HashMap<Entity, Hex>,
     pub blocked_coords: HashSet<Hex>,
+    pub special_coords: HashSet<Hex>,
     pub path_list: HashSet<Hex>,
+    pub path_directions: HashMap<Hex, Hex>, //경로 칸이 어느 방향의 경로인지
+    pub target_list: HashSet<Hex>,
     pub selected_list: HashSet<Hex>,
     pub selected_base: Hex,
-    pub blue_entities: HashSet<Entity>,
-    pub red_entities: HashSet<Entity>,
+    pub selected_last: Hex, //드래그 중 마지막으로 지나간 칸
+    pub hovered: Option<Hex>, //마우스가 올라가 있는 칸
+    pub occupants: HashMap<Hex, Occupant>,
     pub layout: HexLayout,
     pub default_mat: Handle<StandardMaterial>,
-    pub red_mat: Handle<StandardMaterial>,
-    pub blue_mat: Handle<StandardMaterial>,
+    pub special_mat: Handle<StandardMaterial>,
+    pub rock_mat: Handle<StandardMaterial>,
+    pub team_mats: Vec<Handle<StandardMaterial>>,
     pub path_mat: Handle<StandardMaterial>,
+    pub target_mat: Handle<StandardMaterial>,
     pub highlite_mat :Handle<StandardMaterial>,
-    pub seleced_mod: bool,
-    pub path_mod: bool
+    pub invalid_mat: Handle<StandardMaterial>,
+    pub seleced_invalid: bool //선택한 칸들로 만들 수 있는 기물이 없음
+}
+
+impl Map {
+    pub fn team_color(&self, team: Team) -> Handle<StandardMaterial>{
+        self.team_mats[team.0 as usize % self.team_mats.len()].clone()
+    }
+
+    pub fn occupant(&self, hex: Hex) -> Option<&Occupant>{
+        self.occupants.get(&hex)
+    }
+
+    //kind 기물이 있을 때만 돌려줌
+    pub fn occupant_of(&self, hex: Hex, kind: PieceKind) -> Option<&Occupant>{
+        self.occupant(hex).filter(|occupant| occupant.kind == kind)
+    }
+
+    pub fn team_at(&self, hex: Hex) -> Option<Team>{
+        self.occupant(hex).map(|occupant| occupant.team)
+    }
+
+    //다른 팀 기물이 있는 칸
+    pub fn is_enemy(&self, hex: Hex, team: Team) -> bool{
+        self.team_at(hex).map_or(false, |other| other != team)
+    }
+
+    pub fn occupy(&mut self, hex: Hex, occupant: Occupant){
+        if let Some(prev) = self.occupants.insert(hex, occupant){
+            warn!("tile ({}, {}) was still held by {:?}", hex.x, hex.y, prev.kind);
+        }
+    }
+
+    pub fn vacate(&mut self, hex: Hex) -> Option<Occupant>{
+        self.occupants.remove(&hex)
+    }
+
+    //다른 종류의 기물이 이미 올라와 있다면 건드리지 않음
+    pub fn vacate_kind(&mut self, hex: Hex, kind: PieceKind) -> Option<Occupant>{
+        self.occupant_of(hex, kind)?;
+        self.vacate(hex)
+    }
+
+    //칸을 점유한 팀의 색 (없으면 기본색, 특수 칸이면 특수 칸 색)
+    pub fn team_mat(&self, hex: Hex) -> Handle<StandardMaterial>{
+        match self.team_at(hex) {
+            Some(team) => self.team_color(team),
+            None if self.special_coords.contains(&hex) => self.special_mat.clone(),
+            None => self.default_mat.clone()
+        }
+    }
+
+    //드래그로 선택된 칸의 색
+    pub fn selected_mat(&self) -> Handle<StandardMaterial>{
+        if self.seleced_invalid {self.invalid_mat.clone()} else {self.highlite_mat.clone()}
+    }
+
+    //경로, 조준 표시까지 고려한 타일의 현재 색
+    pub fn tile_mat(&self, hex: Hex) -> Handle<StandardMaterial>{
+        if self.is_blocked(hex){
+            self.rock_mat.clone()
+        }
+        else if self.path_list.contains(&hex){
+            self.path_mat.clone()
+        }
+        else if self.target_list.contains(&hex){
+            self.target_mat.clone()
+        }
+        else{
+            self.team_mat(hex)
+        }
+    }
+
+    //6방향 경로를 표시하고 칸마다 방향을 기억함
+    pub fn offer_directions(&mut self, commands: &mut Commands, from: Hex, mut step: impl FnMut(&Self, Hex) -> RayStep){
+        let map: &Self = self;
+        let rays: Vec<(Hex, Vec<Hex>)> = Hex::ZERO.all_neighbors().iter()
+            .map(|direction| (*direction, map.ray(from, *direction, |hex| step(map, hex))))
+            .collect();
+        for (direction, tiles) in rays{
+            for hex in tiles{
+                commands.entity(self.entities[&hex]).insert(self.path_mat.clone());
+                self.path_directions.insert(hex, direction);
+                self.path_list.insert(hex);
+            }
+        }
+    }
+
+    //고를 수 있는 목표 칸을 표시함
+    pub fn offer_tiles(&mut self, commands: &mut Commands, tiles: impl IntoIterator<Item = Hex>){
+        for hex in tiles{
+            commands.entity(self.entities[&hex]).insert(self.target_mat.clone());
+            self.target_list.insert(hex);
+        }
+    }
+
+    //표시한 경로나 목표 칸이면 그 칸이 가리키는 것
+    pub fn aim_at(&self, hex: Hex) -> Option<AimTarget>{
+        if let Some(direction) = self.path_directions.get(&hex){
+            Some(AimTarget::Direction(*direction))
+        }
+        else if self.target_list.contains(&hex){
+            Some(AimTarget::Tile(hex))
+        }
+        else{
+            None
+        }
+    }
+
+    //드래그 선택을 버리고 타일 색을 되돌림
+    pub fn clear_selection(&mut self, commands: &mut Commands){
+        for hex in self.selected_list.drain().collect::<Vec<_>>(){
+            commands.entity(self.entities[&hex]).insert(self.tile_mat(hex));
+        }
+        self.seleced_invalid = false;
+    }
+
+    //조준으로 표시한 경로와 목표 칸을 지움
+    pub fn clear_targets(&mut self, commands: &mut Commands){
+        let tiles: Vec<Hex> = self.path_list.drain().chain(self.target_list.drain()).collect();
+        self.path_directions.clear();
+        for hex in tiles{
+            commands.entity(self.entities[&hex]).insert(self.tile_mat(hex));
+        }
+    }
+}
+
+impl HexBoard for Map{
+    fn contains(&self, hex: Hex) -> bool{
+        self.entities.contains_key(&hex)
+    }
+
+    fn is_blocked(&self, hex: Hex) -> bool{
+        self.blocked_coords.contains(&hex)
+    }
+
+    fn is_occupied(&self, hex: Hex) -> bool{
+        self.occupants.contains_key(&hex)
+    }
 }
 
 #[derive(Component)]
@@ -101,45 +268,69 @@ pub fn setup_grid(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    res_rules: Res<RulesRes>,
+    res_board: Res<BoardRes>
 ){
     let layout = HexLayout {
-        hex_size: Vec2::splat(HEX_SIZE),
+        hex_size: Vec2::splat(res_rules.hex_size),
         ..default()
     };
     // materials
     let default_mat = materials.add(Color::WHITE.into());
-    let blue_mat = materials.add(Color::rgb(0.18, 0.44, 0.725).into());
-    let red_mat = materials.add(Color::rgb(0.858, 0.266, 0.333).into());
+    let team_mats = TEAM_COLORS.iter()
+        .map(|color| materials.add((*color).into()))
+        .collect();
     let highlite_mat = materials.add(Color::LIME_GREEN.into());
+    let invalid_mat = materials.add(Color::rgb(0.45, 0.45, 0.45).into());
     let path_mat = materials.add(Color::rgb(0.4, 0.627, 0.568).into());
+    let target_mat = materials.add(Color::rgb(0.913, 0.647, 0.286).into());
+    let rock_mat = materials.add(Color::rgb(0.36, 0.33, 0.3).into());
+    let special_mat = materials.add(Color::rgb(0.96, 0.89, 0.6).into());
 
     // mesh
-    let mesh = hexagonal_column(HEX_SIZE);
+    let mesh = hexagonal_column(res_rules.hex_size);
     let mesh_handle = meshes.add(mesh);
-    let blocked_coords = HashSet::with_capacity(MAP_RADIUS as usize * 3 );
-    let mut entities_forentity: HashMap<Entity, Hex> = HashMap::with_capacity(MAP_RADIUS as usize * 3);
-    let entities = shapes::hexagon(Hex::ZERO, MAP_RADIUS)
+    let blocked_coords = res_board.tiles_of(TileKind::Blocked);
+    let special_coords = res_board.tiles_of(TileKind::Special);
+    let mut entities_forentity: HashMap<Entity, Hex> = HashMap::with_capacity(res_board.tiles.len());
+    let entities = res_board.tiles.iter()
+        .map(|tile| Hex::new(tile.x, tile.y))
         //.enumerate()
         .map(|hex| {
             let pos = layout.hex_to_world_pos(hex);
-            let id = commands
-                .spawn((
-                    PbrBundle {
-                    transform: Transform::from_xyz(pos.x, 0., pos.y)
-                        .with_scale(Vec3::splat(1.)),
-                    mesh: mesh_handle.clone(),
-                    material: default_mat.clone(),
-                    ..default()
-                    },
-                    Honeycomb,
-                    PickableBundle::default(),
-                    RaycastPickTarget::default(),
-                    On::<Pointer<Over>>::run(on_over),
-                    On::<Pointer<Out>>::run(on_out),
-                    On::<Pointer<Down>>::run(on_click),
-                    //On::<Pointer<Down>>::run(on_click2)
-                ))
-                .id();
+            let id = if blocked_coords.contains(&hex){
+                //바위는 높게 솟은 기둥으로 그리고 선택할 수 없게 피킹을 붙이지 않음
+                commands
+                    .spawn((
+                        PbrBundle {
+                        transform: Transform::from_xyz(pos.x, res_rules.hex_size * 0.6, pos.y)
+                            .with_scale(Vec3::new(1., 3., 1.)),
+                        mesh: mesh_handle.clone(),
+                        material: rock_mat.clone(),
+                        ..default()
+                        },
+                        Honeycomb
+                    ))
+                    .id()
+            }else{
+                commands
+                    .spawn((
+                        PbrBundle {
+                        transform: Transform::from_xyz(pos.x, 0., pos.y),
+                        mesh: mesh_handle.clone(),
+                        material: if special_coords.contains(&hex) {special_mat.clone()} else {default_mat.clone()},
+                        ..default()
+                        },
+                        Honeycomb,
+                        PickableBundle::default(),
+                        RaycastPickTarget::default(),
+                        On::<Pointer<Over>>::run(on_over),
+                        On::<Pointer<Out>>::run(on_out),
+                        On::<Pointer<Down>>::run(on_click),
+                        //On::<Pointer<Down>>::run(on_click2)
+                    ))
+                    .id()
+            };
             entities_forentity.insert(id, hex);
             (hex, id)
         })
@@ -147,119 +338,147 @@ pub fn setup_grid(
     let map = Map {
         entities,
         default_mat,
+        special_mat,
+        rock_mat,
         blocked_coords,
+        special_coords,
         entities_forentity,
         path_list: Default::default(),
+        path_directions: Default::default(),
+        target_list: Default::default(),
         selected_list: Default::default(),
         layout,
-        red_mat,
-        blue_mat,
+        team_mats,
         highlite_mat,
+        invalid_mat,
         path_mat,
-        blue_entities: Default::default(),
-        red_entities: Default::default(),
-        seleced_mod: false,
-        path_mod: false,
-        selected_base: Hex::ZERO
+        target_mat,
+        occupants: Default::default(),
+        seleced_invalid: false,
+        selected_base: Hex::ZERO,
+        selected_last: Hex::ZERO,
+        hovered: None
     };
     //console::log_1(&JsValue::from_str(format!("{:?}\n", map).as_str()));
     commands.insert_resource(map);
 }
 
+//드래그는 타일을 누를 때(on_click) 시작하고 왼쪽 버튼을 놓으면 끝남
 pub fn selected_mod(
-    mut events_seleted: EventReader<MouseButtonInput>,
+    res_mouse: Res<Input<MouseButton>>,
     mut res_grid: ResMut<Map>,
+    mut res_mode: ResMut<InteractionMode>,
     mut events: EventWriter<HexSelecedEndEvent>,
     mut commands: Commands
 ){
-    for ev in events_seleted.iter(){
-        if ev.button == MouseButton::Left{
-            match ev.state {
-                bevy::input::ButtonState::Pressed => {
-                    res_grid.seleced_mod = true;
-                },
-                bevy::input::ButtonState::Released => {
-                    res_grid.seleced_mod = false;
-                    events.send(HexSelecedEndEvent{
-                        seleced_list: res_grid.selected_list.clone(),
-                        base_seleced: res_grid.selected_base
-                    });
-                    for ele in res_grid.selected_list.iter() {
-                        let target = res_grid.entities[ele];
-                        if res_grid.blue_entities.contains(&target){
-                            commands.entity(target).insert(res_grid.blue_mat.clone());
-                        }
-                        else if res_grid.red_entities.contains(&target){
-                            commands.entity(target).insert(res_grid.red_mat.clone());
-                        }
-                        else{
-                            commands.entity(target).insert(res_grid.default_mat.clone());
-                        }
-                    }
-                    res_grid.selected_list.clear();
-                }
-            }
-        }
+    if res_mouse.just_released(MouseButton::Left){
+        events.send(HexSelecedEndEvent{
+            seleced_list: res_grid.selected_list.clone(),
+            base_seleced: res_grid.selected_base
+        });
+        res_grid.clear_selection(&mut commands);
+        *res_mode = InteractionMode::Idle;
     }
 }
 
 fn on_over(
     mut commands: Commands,
     event: Listener<Pointer<Over>>,
-    mut grid: ResMut<Map>
+    mut grid: ResMut<Map>,
+    res_mode: Res<InteractionMode>
 ){
     let target = event.target;
     let grid_target = grid.entities_forentity[&target];
-    if grid.seleced_mod{
-        let seleced_hex = grid_target;
-        grid.selected_list.insert(seleced_hex);
-        commands.entity(target).insert(
-            grid.highlite_mat.clone()
-        );
+    if *res_mode == InteractionMode::DragSelecting{
+        //마우스를 빠르게 움직여 건너뛴 칸도 이어서 선택함
+        let mat = grid.selected_mat();
+        //바위는 건너뜀
+        let line: Vec<Hex> = grid.line(grid.selected_last, grid_target).into_iter()
+            .filter(|hex| grid.is_passable(*hex))
+            .collect();
+        for hex in line{
+            grid.selected_list.insert(hex);
+            commands.entity(grid.entities[&hex]).insert(mat.clone());
+        }
+        grid.selected_last = grid_target;
     }else{
         commands.entity(target).insert(
             grid.highlite_mat.clone()
         );
     }
-    grid.path_mod = grid.path_list.contains(&grid_target);
+    grid.hovered = Some(grid_target);
 }
 
 fn on_out(
     mut commands: Commands,
     event: Listener<Pointer<Out>>,
-    grid: Res<Map>,
+    mut grid: ResMut<Map>,
 ){
     let target = event.target;
     let target_hex = grid.entities_forentity[&target];
+    if grid.hovered == Some(target_hex){
+        grid.hovered = None;
+    }
     if grid.selected_list.contains(&target_hex){
-        commands.entity(target).insert(grid.highlite_mat.clone());
+        commands.entity(target).insert(grid.selected_mat());
     }
     else if grid.path_list.contains(&target_hex){
         commands.entity(target).insert(grid.path_mat.clone());
     }
-    else if grid.blue_entities.contains(&target){
-        commands.entity(target).insert(grid.blue_mat.clone());
-    }
-    else if grid.red_entities.contains(&target){
-        commands.entity(target).insert(grid.red_mat.clone());
+    else if grid.target_list.contains(&target_hex){
+        commands.entity(target).insert(grid.target_mat.clone());
     }
     else{
-        commands.entity(target).insert(grid.default_mat.clone());
+        commands.entity(target).insert(grid.team_mat(target_hex));
     }
 }
 
 fn on_click(
     mut commands: Commands,
     event: Listener<Pointer<Down>>,
-    mut grid: ResMut<Map>
+    mut grid: ResMut<Map>,
+    mut res_mode: ResMut<InteractionMode>
 ){
     let target = event.target;
-    if grid.seleced_mod{
+    //다른 일을 하고 있지 않을 때만 누른 칸을 기준으로 드래그 선택을 시작함
+    if event.button == PointerButton::Primary && *res_mode == InteractionMode::Idle{
+        *res_mode = InteractionMode::DragSelecting;
         let seleced_hex = grid.entities_forentity[&target];
         grid.selected_base = seleced_hex;
+        grid.selected_last = seleced_hex;
         grid.selected_list.insert(seleced_hex);
         commands.entity(target).insert(
             grid.highlite_mat.clone()
         );
     }
 }
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    //엔티티 번호만 채운 반지름 radius 의 보드
+    fn test_map(radius: u32) -> Map{
+        let entities = Hex::ZERO.range(radius)
+            .enumerate()
+            .map(|(i, hex)| (hex, Entity::from_raw(i as u32)))
+            .collect();
+        Map { entities, ..Default::default() }
+    }
+
+    //화면의 보드도 시뮬레이션과 같은 기준으로 바위와 기물을 봄
+    #[test]
+    fn rocks_and_pieces_cut_rays_on_the_map(){
+        let mut map = test_map(3);
+        map.blocked_coords.insert(Hex::new(2, 0));
+        map.occupy(Hex::new(0, 2), Occupant { team: Team::RED, kind: PieceKind::Pawn, entity: Entity::from_raw(100) });
+        assert!(!map.is_clear(Hex::new(2, 0)));
+        assert!(!map.is_clear(Hex::new(0, 2)));
+        assert!(map.is_clear(Hex::new(1, 0)));
+
+        let to_rock = map.ray(Hex::ZERO, Hex::new(1, 0), |hex| if map.is_blocked(hex) {RayStep::Stop} else {RayStep::Mark});
+        assert_eq!(to_rock, vec![Hex::new(1, 0)]);
+        let past_piece = map.ray(Hex::ZERO, Hex::new(0, 1), |hex| if map.is_occupied(hex) {RayStep::Skip} else {RayStep::Mark});
+        assert_eq!(past_piece, vec![Hex::new(0, 1), Hex::new(0, 3)]);
+    }
+}
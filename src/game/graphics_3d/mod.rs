@@ -2,6 +2,8 @@ pub mod honeycomb;
 use bevy::prelude::*;
 use honeycomb::*;
 
+use crate::game::chess::interaction::{InteractionMode, in_mode};
+
 fn spawn_light(
     mut commands: Commands
 ){
@@ -32,13 +34,13 @@ impl Plugin for Graphics3dPlugins{
         // .init_resource::<HuneycombRes>()
         // .add_state::<HuneycombSpawnState>()
         .add_event::<HexSelecedEndEvent>()
+        //보드(setup_grid)는 맵이 로드된 뒤 ChessGamePlugin에서 만듦
         .add_systems(Startup, (
             spawn_light,
-            setup_grid
         ))
         .add_systems(Update, (
             selected_mod,
-        ))
+        ).run_if(resource_exists::<Map>()).run_if(in_mode(InteractionMode::DragSelecting)))
         ;
         // .add_systems(Update, (
         //     camera_spin,